@@ -0,0 +1,95 @@
+use tetris_wasm::models::action::Action;
+use tetris_wasm::models::board::Board;
+use tetris_wasm::models::scene::Scene;
+
+// Weights from Yiyuan Lee's tuned heuristic, good enough to clear thousands
+// of lines with plain bag randomizers.
+const HEIGHT_WEIGHT: f32 = -0.510_066;
+const LINES_WEIGHT: f32 = 0.760_666;
+const HOLES_WEIGHT: f32 = -0.356_63;
+const BUMPINESS_WEIGHT: f32 = -0.184_483;
+
+// Tries every rotation and column for the current piece on a copy of the
+// scene and returns the actions that lead to the best looking heap.
+pub fn plan(scene: &Scene) -> Vec<Action> {
+    let mut best: Option<(f32, Vec<Action>)> = None;
+
+    for turns in 0..4 {
+        let mut turned = scene.clone();
+        if !(0..turns).all(|_| turned.rotate_figure()) {
+            continue;
+        }
+
+        for &(dx, shift) in [(-1, Action::MoveLeft), (1, Action::MoveRight)].iter() {
+            let mut moved = turned.clone();
+            let mut shifts = 0;
+            loop {
+                let mut dropped = moved.clone();
+                dropped.hard_drop_figure();
+                let score = evaluate(&dropped.get_merged());
+                if best.as_ref().is_none_or(|(b, _)| score > *b) {
+                    let mut actions = vec![Action::Rotate; turns];
+                    actions.extend(vec![shift; shifts]);
+                    actions.push(Action::HardDrop);
+                    best = Some((score, actions));
+                }
+
+                if !moved.move_figure_x(dx) {
+                    break;
+                }
+                shifts += 1;
+            }
+        }
+    }
+
+    best.map_or(vec![Action::HardDrop], |(_, actions)| actions)
+}
+
+fn evaluate(board: &Board) -> f32 {
+    let (width, height) = (board.width(), board.height());
+    let mut heights = vec![0; width];
+    let mut holes = 0;
+    for (c, column_height) in heights.iter_mut().enumerate() {
+        if let Some(top) = (0..height).find(|&r| board.cell(r, c).is_filled()) {
+            *column_height = height - top;
+            holes += (top..height)
+                .filter(|&r| !board.cell(r, c).is_filled())
+                .count();
+        }
+    }
+
+    let lines = board
+        .iter()
+        .filter(|row| row.iter().all(|c| c.is_filled()))
+        .count();
+    let total: usize = heights.iter().sum();
+    let bumpiness: usize = heights
+        .windows(2)
+        .map(|pair| (pair[0] as isize - pair[1] as isize).unsigned_abs())
+        .sum();
+
+    HEIGHT_WEIGHT * total as f32
+        + LINES_WEIGHT * lines as f32
+        + HOLES_WEIGHT * holes as f32
+        + BUMPINESS_WEIGHT * bumpiness as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tetris_wasm::models::game::Game;
+    use tetris_wasm::models::game_status::GameStatus;
+
+    #[test]
+    fn check_bot_clears_lines() {
+        let mut game = Game::with_seed(0);
+        game.apply(Action::Start);
+        for _ in 0..50 {
+            for action in plan(game.get_scene()) {
+                game.apply(action);
+            }
+        }
+        assert_eq!(game.get_status(), GameStatus::InProgress);
+        assert!(game.get_state().score.lines >= 15);
+    }
+}
@@ -0,0 +1,168 @@
+mod bot;
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::{env, fs, process};
+use tetris_wasm::models::action::Action;
+use tetris_wasm::models::event::GameEvent;
+use tetris_wasm::models::game::Game;
+use tetris_wasm::models::game_status::GameStatus;
+use tetris_wasm::models::randomizer::RandomizerKind;
+use tetris_wasm::models::settings::Settings;
+
+const USAGE: &str = "usage: simulate [--games N] [--seed N] [--max-pieces N]
+                [--randomizer random|bag|history] [--script FILE]
+
+Plays seeded games without a frontend and prints one JSON line per game.
+Game i is dealt with seed + i. Without a script the built-in bot plays
+until it tops out or places max-pieces pieces. A script holds one action
+per line, either by name (HardDrop) or as JSON ({\"Garbage\":{\"rows\":2}}),
+and is played once from the top in every game.";
+
+struct Options {
+    games: u64,
+    seed: u64,
+    max_pieces: u32,
+    randomizer: RandomizerKind,
+    script: Option<Vec<Action>>,
+}
+
+#[derive(Serialize)]
+struct Stats {
+    game: u64,
+    seed: u64,
+    pieces: u32,
+    lines: u32,
+    score: u32,
+    level: u32,
+    tetrises: u32,
+    tspins: u32,
+    perfect_clears: u32,
+    over: Option<String>,
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: Option<String>) -> T {
+    value
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| fail(&format!("{} needs a number", name)))
+}
+
+fn parse_script(path: &str) -> Vec<Action> {
+    let text =
+        fs::read_to_string(path).unwrap_or_else(|e| fail(&format!("cannot read {}: {}", path, e)));
+
+    let lines = text.lines().map(str::trim).enumerate();
+    lines
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .or_else(|_| serde_json::from_str(&format!("\"{}\"", line)))
+                .unwrap_or_else(|_| fail(&format!("{}:{}: unknown action {}", path, i + 1, line)))
+        })
+        .collect()
+}
+
+fn parse_options() -> Options {
+    let mut options = Options {
+        games: 100,
+        seed: 0,
+        max_pieces: 1000,
+        randomizer: RandomizerKind::Bag,
+        script: None,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--games" => options.games = parse_number(&arg, args.next()),
+            "--seed" => options.seed = parse_number(&arg, args.next()),
+            "--max-pieces" => options.max_pieces = parse_number(&arg, args.next()),
+            "--randomizer" => {
+                options.randomizer = match args.next().as_deref() {
+                    Some("random") => RandomizerKind::Random,
+                    Some("bag") => RandomizerKind::Bag,
+                    Some("history") => RandomizerKind::History,
+                    _ => fail("--randomizer is one of random, bag or history"),
+                }
+            }
+            "--script" => match args.next() {
+                Some(path) => options.script = Some(parse_script(&path)),
+                None => fail("--script needs a file"),
+            },
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ => fail(&format!("unknown option {}", arg)),
+        }
+    }
+    options
+}
+
+// The script or the bot decides what comes next, None once it has nothing
+// left to play.
+fn next_actions(
+    options: &Options,
+    game: &Game,
+    script: &mut VecDeque<Action>,
+) -> Option<Vec<Action>> {
+    match options.script {
+        Some(_) => script.pop_front().map(|action| vec![action]),
+        None => Some(bot::plan(game.get_scene())),
+    }
+}
+
+fn simulate(options: &Options, index: u64) -> Stats {
+    let seed = options.seed.wrapping_add(index);
+    let mut settings = Settings::new();
+    settings.seed = seed;
+    settings.randomizer = options.randomizer;
+    settings.undo = 0;
+
+    let mut game = Game::with_settings(settings);
+    let mut script: VecDeque<Action> = options.script.iter().flatten().copied().collect();
+    let mut pieces = 0;
+    game.apply(Action::Start);
+
+    while game.get_status() != GameStatus::Over && pieces < options.max_pieces {
+        let actions = match next_actions(options, &game, &mut script) {
+            Some(actions) => actions,
+            None => break,
+        };
+        for action in actions {
+            game.apply(action);
+        }
+        let locked = game.drain_events().into_iter();
+        pieces += locked
+            .filter(|e| matches!(e, GameEvent::PieceLocked { .. }))
+            .count() as u32;
+    }
+
+    let state = game.get_state();
+    let score = state.score;
+    Stats {
+        game: index,
+        seed,
+        pieces,
+        lines: score.lines,
+        score: score.points,
+        level: score.level,
+        tetrises: score.tetrises,
+        tspins: score.tspins,
+        perfect_clears: score.perfect_clears,
+        over: state.over.map(|reason| format!("{:?}", reason)),
+    }
+}
+
+fn main() {
+    let options = parse_options();
+    for index in 0..options.games {
+        let stats = simulate(&options, index);
+        println!("{}", serde_json::to_string(&stats).unwrap());
+    }
+}
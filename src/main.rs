@@ -1,12 +1,15 @@
-#![allow(unused)]
+#[cfg(unix)]
+mod terminal;
 
-mod models;
+#[cfg(unix)]
+fn main() {
+    if let Err(error) = terminal::run() {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
 
+#[cfg(not(unix))]
 fn main() {
-    // println!(
-    //     "Hello, world {}!",
-    //     exercises::assembly_line::working_items_per_minute(10)
-    // );
-    // exercises::health_statistics::test_link()
-    // println!("{}", exercises::clock::Clock::new(25, 60))
+    eprintln!("the terminal frontend needs a unix terminal");
 }
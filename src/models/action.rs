@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+// Everything a player or the frontend loop can ask a game to do.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Start,
+    Pause,
+    Resume,
+    Tick,
+    MoveLeft,
+    MoveRight,
+    MoveDown,
+    HardDrop,
+    Rotate,
+    RotateCcw,
+    Rotate180,
+    Hold,
+    Undo,
+    Garbage { rows: usize },
+}
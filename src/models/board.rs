@@ -1,64 +1,144 @@
-use serde::Serialize;
-use serde_repr::Serialize_repr;
+use crate::models::shape::ShapeKind;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::ops::{BitAnd, BitOr};
 
-pub type BoardMatrix = [[BoardCell; BOARD_WIDTH]; BOARD_HEIGHT];
+pub type BoardMatrix = Vec<Vec<BoardCell>>;
 
-pub const BOARD_WIDTH: usize = 10;
-pub const BOARD_HEIGHT: usize = 18;
+// The guideline playfield, other sizes are picked in the settings.
+pub const STANDARD_WIDTH: usize = 10;
+pub const STANDARD_HEIGHT: usize = 20;
+// Hidden rows above the visible field, pieces spawn in the lowest two of them.
+pub const STANDARD_BUFFER: usize = 20;
+pub const SPAWN_ROWS: usize = 2;
 
-#[derive(Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum BoardCell {
     Empty = 0,
-    Filled = 1,
+    T = 1,
+    I = 2,
+    O = 3,
+    Z = 4,
+    S = 5,
+    J = 6,
+    L = 7,
+    Garbage = 8,
+    Ghost = 9,
 }
 
 impl From<u8> for BoardCell {
     fn from(i: u8) -> Self {
         match i {
             0 => BoardCell::Empty,
-            1 => BoardCell::Filled,
+            1 => BoardCell::T,
+            2 => BoardCell::I,
+            3 => BoardCell::O,
+            4 => BoardCell::Z,
+            5 => BoardCell::S,
+            6 => BoardCell::J,
+            7 => BoardCell::L,
+            8 => BoardCell::Garbage,
+            9 => BoardCell::Ghost,
             _ => panic!("unknown index {:?}", i),
         }
     }
 }
 
+impl From<ShapeKind> for BoardCell {
+    fn from(kind: ShapeKind) -> Self {
+        BoardCell::from(kind as u8 + 1)
+    }
+}
+
+impl BoardCell {
+    // Ghost cells are only drawn, they never block anything.
+    pub fn is_filled(self) -> bool {
+        !matches!(self, BoardCell::Empty | BoardCell::Ghost)
+    }
+}
+
+// Both cells are occupied, the left one is kept.
 impl BitAnd for BoardCell {
     type Output = Self;
 
     fn bitand(self, rhs: Self) -> Self::Output {
-        BoardCell::from(self as u8 & rhs as u8)
+        if self.is_filled() && rhs.is_filled() {
+            self
+        } else {
+            BoardCell::Empty
+        }
     }
 }
 
+// Either cell is occupied, the left one wins when both are.
 impl BitOr for BoardCell {
     type Output = Self;
 
     fn bitor(self, rhs: Self) -> Self::Output {
-        BoardCell::from(self as u8 | rhs as u8)
+        if self.is_filled() || rhs == BoardCell::Empty {
+            self
+        } else {
+            rhs
+        }
     }
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Board(BoardMatrix);
-//  {
-//     pub matrix: [[BoardCell; BOARD_WIDTH]; BOARD_HEIGHT],
-// }
 
 impl Board {
-    pub fn empty() -> BoardMatrix {
-        [[BoardCell::Empty; BOARD_WIDTH]; BOARD_HEIGHT]
+    pub fn empty(width: usize, height: usize) -> BoardMatrix {
+        vec![vec![BoardCell::Empty; width]; height]
+    }
+
+    pub fn new(width: usize, height: usize) -> Self {
+        Board(Board::empty(width, height))
     }
 
-    pub fn new() -> Self {
-        Board(Board::empty())
+    pub fn width(&self) -> usize {
+        self.0.first().map_or(0, Vec::len)
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &[BoardCell; BOARD_WIDTH]> {
+    pub fn height(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Vec<BoardCell>> {
         self.0.iter()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().flatten().all(|c| !c.is_filled())
+    }
+
+    pub fn cell(&self, r: usize, c: usize) -> BoardCell {
+        self.0[r][c]
+    }
+
+    pub fn map(&self, f: impl Fn(BoardCell) -> BoardCell) -> Board {
+        Board(
+            self.0
+                .iter()
+                .map(|row| row.iter().map(|&c| f(c)).collect())
+                .collect(),
+        )
+    }
+
+    // Pushes the heap up by the given rows, returns whether anything was pushed
+    // out of the top.
+    pub fn raise(&mut self, rows: usize, row: &[BoardCell]) -> bool {
+        let rows = rows.min(self.height());
+        let toppled = self.0[..rows].iter().flatten().any(|c| c.is_filled());
+        self.0.drain(..rows);
+        self.0.extend((0..rows).map(|_| row.to_vec()));
+        toppled
+    }
+
+    pub fn crop_top(&self, rows: usize) -> Board {
+        Board(self.0[rows..].to_vec())
+    }
+
     pub fn merge_with(&self, b2: &Board) -> Board {
         Board::merge(self, b2)
     }
@@ -67,25 +147,15 @@ impl Board {
         Board::intersection(self, b2)
     }
 
-    // pub fn f(&mut self) -> Board {
-    //     let mut result = Board::empty();
-
-    //     // for (r, row) in self.0.iter().enumerate() {
-    //     //     if row.iter().all(|&c| c == BoardCell::Filled) {
-    //     //         self.0 =
-    //     //     }
-    //     // }
-    // }
-
     pub fn remove_line(&mut self, i: usize) {
-        let first = self.0[0..i].to_vec();
-        let second = self.0[i + 1..].to_vec();
-        let concat = [vec![[BoardCell::Empty; BOARD_WIDTH]], first, second].concat();
-        *self = Board::from(&concat)
+        let width = self.width();
+        self.0.remove(i);
+        self.0.insert(0, vec![BoardCell::Empty; width]);
     }
 
     pub fn merge(b1: &Board, b2: &Board) -> Board {
-        let mut result = Board::new();
+        debug_assert_eq!(b1.0.len(), b2.0.len());
+        let mut result = Board::new(b1.width(), b1.height());
 
         for (r, row) in result.0.iter_mut().enumerate() {
             for (c, cell) in row.iter_mut().enumerate() {
@@ -97,9 +167,10 @@ impl Board {
     }
 
     pub fn intersection(b1: &Board, b2: &Board) -> bool {
+        debug_assert_eq!(b1.0.len(), b2.0.len());
         for (r, row) in b1.0.iter().enumerate() {
             for (c, _) in row.iter().enumerate() {
-                if b1.0[r][c] & b2.0[r][c] == BoardCell::Filled {
+                if (b1.0[r][c] & b2.0[r][c]).is_filled() {
                     return true;
                 }
             }
@@ -115,66 +186,87 @@ impl From<BoardMatrix> for Board {
     }
 }
 
-impl From<&Vec<[BoardCell; BOARD_WIDTH]>> for Board {
-    fn from(m: &Vec<[BoardCell; BOARD_WIDTH]>) -> Self {
-        let mut result = Board::empty();
-
-        for (r, row) in result.iter_mut().enumerate() {
-            *row = *m.get(r).unwrap_or(row)
-        }
-
-        Board(result)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn check_board_merge() {
-        let mut b1 = Board::new();
-        let mut b2 = Board::new();
+        let mut b1 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
+        let mut b2 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
 
-        b1.0[0][0] = BoardCell::Filled;
-        b2.0[0][1] = BoardCell::Filled;
+        b1.0[0][0] = BoardCell::T;
+        b2.0[0][1] = BoardCell::I;
 
-        let mut compare = Board::new();
-        compare.0[0][0] = BoardCell::Filled;
-        compare.0[0][1] = BoardCell::Filled;
+        let mut compare = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
+        compare.0[0][0] = BoardCell::T;
+        compare.0[0][1] = BoardCell::I;
 
         assert_eq!(Board::merge(&b1, &b2), compare);
     }
 
+    #[test]
+    fn check_ghost_does_not_occupy() {
+        let mut b1 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
+        let mut b2 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
+
+        b1.0[0][0] = BoardCell::Ghost;
+        b2.0[0][0] = BoardCell::S;
+
+        assert!(!Board::intersection(&b1, &b2));
+        assert_eq!(Board::merge(&b1, &b2).cell(0, 0), BoardCell::S);
+        assert_eq!(
+            Board::merge(&b1, &Board::new(STANDARD_WIDTH, STANDARD_HEIGHT)).cell(0, 0),
+            BoardCell::Ghost
+        );
+    }
+
     #[test]
     fn check_board_don_t_intersect() {
-        let mut b1 = Board::new();
-        let mut b2 = Board::new();
+        let mut b1 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
+        let mut b2 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
 
-        b1.0[0][0] = BoardCell::Filled;
-        b2.0[0][1] = BoardCell::Filled;
+        b1.0[0][0] = BoardCell::Garbage;
+        b2.0[0][1] = BoardCell::Garbage;
 
         assert!(!Board::intersection(&b1, &b2));
     }
 
     #[test]
     fn check_board_intersect() {
-        let mut b1 = Board::new();
-        let mut b2 = Board::new();
+        let mut b1 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
+        let mut b2 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
 
-        b1.0[0][0] = BoardCell::Filled;
-        b2.0[0][0] = BoardCell::Filled;
+        b1.0[0][0] = BoardCell::Garbage;
+        b2.0[0][0] = BoardCell::Garbage;
 
         assert!(Board::intersection(&b1, &b2));
     }
 
     #[test]
     fn check_remove_line() {
-        let mut b1 = Board::new();
+        let mut b1 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
 
-        b1.0[1] = [BoardCell::Filled; BOARD_WIDTH];
+        b1.0[1] = vec![BoardCell::Garbage; STANDARD_WIDTH];
         b1.remove_line(1);
 
-        assert_eq!(b1, Board::new());
+        assert_eq!(b1, Board::new(STANDARD_WIDTH, STANDARD_HEIGHT));
+    }
+
+    #[test]
+    fn check_raise_pushes_rows_up() {
+        let mut b1 = Board::new(STANDARD_WIDTH, STANDARD_HEIGHT);
+        let mut row = vec![BoardCell::Garbage; STANDARD_WIDTH];
+        row[0] = BoardCell::Empty;
+
+        assert!(!b1.raise(2, &row));
+        assert_eq!(b1.height(), STANDARD_HEIGHT);
+        assert_eq!(b1.0[STANDARD_HEIGHT - 1], row);
+        assert_eq!(
+            b1.0[STANDARD_HEIGHT - 3],
+            vec![BoardCell::Empty; STANDARD_WIDTH]
+        );
+
+        assert!(b1.raise(STANDARD_HEIGHT - 1, &row));
     }
 }
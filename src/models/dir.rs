@@ -7,3 +7,10 @@ pub enum VDir {
     Top,
     Bottom,
 }
+
+// Shapes only ever turn a quarter at a time, matrices can also mirror.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Cw,
+    Ccw,
+}
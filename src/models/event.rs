@@ -0,0 +1,23 @@
+use super::game_status::GameOver;
+use super::score::Spin;
+use super::shape::ShapeKind;
+use serde::Serialize;
+
+// Things that happened during an action, for frontends to animate or play
+// sounds on without diffing boards.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    PieceSpawned { kind: ShapeKind },
+    PieceLocked { kind: ShapeKind, spin: Spin },
+    PieceHeld { kind: ShapeKind },
+    // The last locked piece was taken back and is up again.
+    Undone { kind: ShapeKind },
+    // Rows are counted from the top of the reported board.
+    LinesCleared { rows: Vec<usize> },
+    PerfectClear,
+    RotationFailed,
+    LevelUp { level: u32 },
+    GameOver { reason: GameOver },
+    ActionRejected,
+}
@@ -1,83 +1,801 @@
-use super::board::Board;
-use super::game_status::GameStatus;
+use super::action::Action;
+use super::board::{Board, STANDARD_HEIGHT, STANDARD_WIDTH};
+use super::event::GameEvent;
+use super::game_status::{GameOver, GameStatus};
+use super::gravity::{frame_interval, Gravity};
+use super::matrix::MatrixBody;
+use super::randomizer::Sequence;
+use super::replay::Recording;
 use super::scene::Scene;
-use serde::Serialize;
+use super::score::Score;
+use super::settings::Settings;
+use super::shape::ShapeKind;
+use super::snapshot;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
 use wasm_bindgen::prelude::*;
 
-#[derive(Serialize)]
-struct GameState {
-    blocks: Board,
-    status: GameStatus,
+#[derive(Serialize, Debug, PartialEq)]
+pub struct GameState {
+    pub width: usize,
+    pub height: usize,
+    pub spawn_rows: usize,
+    pub blocks: Board,
+    pub ghost: Board,
+    pub status: GameStatus,
+    pub over: Option<GameOver>,
+    pub rejected: bool,
+    pub score: Score,
+    pub interval: u32,
+    pub next: Vec<MatrixBody>,
+    pub hold: Option<MatrixBody>,
 }
 
+// Events only describe the last actions, a saved game comes back without them.
 #[wasm_bindgen]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Game {
     scene: Scene,
+    settings: Settings,
     status: GameStatus,
+    over: Option<GameOver>,
+    score: Score,
+    fall: f32,
+    lock: Option<u32>,
+    lock_resets: u32,
+    lowest: i8,
+    #[serde(skip)]
+    events: Vec<GameEvent>,
+    frame: u32,
+    recording: Recording,
+    // The scene and score as each of the last few pieces spawned, the
+    // current one last.
+    history: VecDeque<(Scene, Score)>,
 }
 
 #[wasm_bindgen]
 impl Game {
-    pub fn new() -> Self {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut settings = Settings::new();
+        settings.width = width;
+        settings.height = height;
+        Game::with_settings(settings)
+    }
+
+    pub fn standard() -> Self {
+        Game::new(STANDARD_WIDTH, STANDARD_HEIGHT)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut settings = Settings::new();
+        settings.seed = seed;
+        Game::with_settings(settings)
+    }
+
+    pub fn with_settings(settings: Settings) -> Self {
+        let recording = Recording::new(settings, None);
+        Game::with_scene(Scene::with_settings(&settings), settings, recording)
+    }
+
+    // Throws on the JS side for an empty sequence or unknown shapes.
+    pub fn with_sequence(settings: Settings, kinds: Vec<u8>) -> Result<Game, JsValue> {
+        Game::from_sequence(settings, kinds).map_err(|e| JsValue::from_str(&e))
+    }
+
+    fn with_scene(scene: Scene, settings: Settings, recording: Recording) -> Self {
         Game {
-            scene: Scene::new(),
+            scene,
+            settings,
             status: GameStatus::Pending,
+            over: None,
+            score: Score::new(),
+            fall: 0.0,
+            lock: None,
+            lock_resets: 0,
+            lowest: 0,
+            events: vec![],
+            frame: 0,
+            recording,
+            history: VecDeque::new(),
         }
     }
 
     pub fn move_left(&mut self) -> JsValue {
-        self.scene.move_figure_x(-1);
-        self.to_js()
+        self.act(Action::MoveLeft)
     }
 
     pub fn move_right(&mut self) -> JsValue {
-        self.scene.move_figure_x(1);
-        self.to_js()
+        self.act(Action::MoveRight)
+    }
+
+    fn shift(&mut self, dx: i8) {
+        let moved = self.scene.move_figure_x(dx);
+        self.update_lock(moved);
     }
 
     pub fn move_down(&mut self) -> JsValue {
+        self.act(Action::MoveDown)
+    }
+
+    fn soft_drop(&mut self) {
         let touched = self.scene.move_figure_y(1);
-        
-        if !touched {
-            return self.to_js() 
+        self.update_lock(!touched);
+    }
+
+    pub fn hard_drop(&mut self) -> JsValue {
+        self.act(Action::HardDrop)
+    }
+
+    fn drop_figure(&mut self) {
+        let rows = self.scene.hard_drop_figure();
+        self.score.credit_hard_drop(rows);
+        self.lock_figure();
+    }
+
+    fn lock_figure(&mut self) {
+        let kind = self.scene.get_figure_kind();
+        let level = self.score.level;
+        let locked_out = self.scene.check_locks_out();
+        let lock = self.scene.credit();
+        self.score.credit(&lock);
+
+        self.emit(GameEvent::PieceLocked {
+            kind,
+            spin: lock.spin,
+        });
+        if lock.lines > 0 {
+            let hidden = self.scene.hidden_rows();
+            let rows = self.scene.get_cleared().iter();
+            let rows = rows.filter_map(|r| r.checked_sub(hidden)).collect();
+            self.emit(GameEvent::LinesCleared { rows });
+        }
+        if lock.perfect_clear {
+            self.emit(GameEvent::PerfectClear);
+        }
+        if self.score.level > level {
+            let level = self.score.level;
+            self.emit(GameEvent::LevelUp { level });
         }
 
-        self.scene.credit();
+        if locked_out {
+            self.end(GameOver::LockOut);
+            return;
+        }
         self.scene.reset_figure();
+        self.spawned();
+        self.reset_lock();
+        self.checkpoint();
+    }
 
-        self.status = if self.scene.check_shape_intersects_heap() {
-            GameStatus::Over
-        } else {
-            GameStatus::InProgress
-        };
+    fn spawned(&mut self) {
+        let kind = self.scene.get_figure_kind();
+        self.emit(GameEvent::PieceSpawned { kind });
+        self.check_over();
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    // Everything that happened since the last call, oldest first.
+    pub fn events(&mut self) -> JsValue {
+        to_js(&self.drain_events())
+    }
 
-        self.to_js()
+    fn reset_lock(&mut self) {
+        self.fall = 0.0;
+        self.lock = None;
+        self.lock_resets = 0;
+        self.lowest = self.scene.get_figure_y();
+    }
+
+    // Starts the lock timer once the shape rests on something, and restarts it
+    // on a successful move until the resets for the current row run out. The
+    // timer belongs to the lowest row reached, so lifting the shape off it only
+    // stops the clock until the shape lands there again.
+    fn update_lock(&mut self, moved: bool) {
+        let y = self.scene.get_figure_y();
+        if y > self.lowest {
+            self.lowest = y;
+            self.lock_resets = 0;
+            self.lock = None;
+        }
+
+        match self.lock {
+            None if self.scene.check_grounded() => self.lock = Some(0),
+            Some(_) if moved && self.lock_resets < self.settings.lock_resets => {
+                self.lock = Some(0);
+                self.lock_resets += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn lock_running(&self) -> bool {
+        self.lock.is_some() && self.scene.check_grounded()
+    }
+
+    fn check_over(&mut self) {
+        if self.scene.check_shape_intersects_heap() {
+            self.end(GameOver::BlockOut);
+        }
+    }
+
+    fn end(&mut self, reason: GameOver) {
+        self.status = GameStatus::Over;
+        self.over = Some(reason);
+        self.lock = None;
+        self.emit(GameEvent::GameOver { reason });
+    }
+
+    // Garbage sent by an opponent or a drill mode, with one random hole.
+    pub fn add_garbage(&mut self, rows: usize) -> JsValue {
+        self.act(Action::Garbage { rows })
+    }
+
+    fn raise(&mut self, rows: usize) {
+        if self.scene.add_garbage(rows) {
+            self.end(GameOver::TopOut);
+        } else {
+            self.update_lock(false);
+        }
     }
 
     pub fn rotate(&mut self) -> JsValue {
-        self.scene.rotate_figure();
-        self.to_js()
+        self.act(Action::Rotate)
+    }
+
+    pub fn rotate_ccw(&mut self) -> JsValue {
+        self.act(Action::RotateCcw)
+    }
+
+    pub fn rotate_180(&mut self) -> JsValue {
+        self.act(Action::Rotate180)
+    }
+
+    fn turn(&mut self, rotate: fn(&mut Scene) -> bool) {
+        let moved = rotate(&mut self.scene);
+        if !moved {
+            self.emit(GameEvent::RotationFailed);
+        }
+        self.update_lock(moved);
+    }
+
+    pub fn hold(&mut self) -> JsValue {
+        self.act(Action::Hold)
+    }
+
+    fn hold_figure(&mut self) {
+        let kind = self.scene.get_figure_kind();
+        if self.scene.hold_figure() {
+            self.emit(GameEvent::PieceHeld { kind });
+            self.spawned();
+            self.reset_lock();
+        }
+    }
+
+    pub fn undo(&mut self) -> JsValue {
+        self.act(Action::Undo)
+    }
+
+    fn checkpoint(&mut self) {
+        let depth = self.settings.undo_depth();
+        if depth == 0 || self.status != GameStatus::InProgress {
+            return;
+        }
+        self.history.push_back((self.scene.clone(), self.score));
+        while self.history.len() > depth + 1 {
+            self.history.pop_front();
+        }
+    }
+
+    // Puts the last locked piece back at the top, along with the heap, queue
+    // and score it spawned with. Once the game is over, the piece that ended
+    // it is taken back and the game goes on.
+    fn take_back(&mut self) -> bool {
+        let over = self.status == GameStatus::Over;
+        let needed = if over { 1 } else { 2 };
+        if self.history.len() < needed {
+            return self.reject();
+        }
+        if !over {
+            self.history.pop_back();
+        }
+        let (scene, score) = self.history.back().cloned().unwrap();
+        self.scene = scene;
+        self.score = score;
+        self.status = GameStatus::InProgress;
+        self.over = None;
+        self.reset_lock();
+
+        let kind = self.scene.get_figure_kind();
+        self.emit(GameEvent::Undone { kind });
+        true
+    }
+
+    fn reject(&mut self) -> bool {
+        self.emit(GameEvent::ActionRejected);
+        false
+    }
+
+    // Only the state returned for an action says whether it was rejected.
+    fn act(&mut self, action: Action) -> JsValue {
+        let accepted = self.apply(action);
+        let mut state = self.get_state();
+        state.rejected = !accepted;
+        to_js(&state)
+    }
+
+    // The whole game so far, ready to be handed to a `Replay`.
+    pub fn recording(&self) -> JsValue {
+        to_js(&self.recording)
+    }
+
+    // Everything needed to carry on with the game later, e.g. after a reload.
+    pub fn save(&self) -> String {
+        snapshot::save(self)
+    }
+
+    // Throws on the JS side for corrupt or outdated saves.
+    pub fn load(snapshot: &str) -> Result<Game, JsValue> {
+        snapshot::load(snapshot).map_err(|e| JsValue::from_str(&e))
     }
 
     pub fn to_js(&self) -> JsValue {
-        JsValue::from_serde(&self.get_state()).unwrap()
+        to_js(&self.get_state())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.settings.seed
     }
 
-    fn get_state(&self) -> GameState {
+    // While the lock timer runs the game wants a tick every frame.
+    pub fn interval(&self) -> u32 {
+        if self.lock_running() {
+            frame_interval()
+        } else {
+            self.gravity().interval()
+        }
+    }
+
+    fn gravity(&self) -> Gravity {
+        Gravity::for_level(self.score.level)
+    }
+
+    pub fn start(&mut self) -> JsValue {
+        self.act(Action::Start)
+    }
+
+    pub fn pause(&mut self) -> JsValue {
+        self.act(Action::Pause)
+    }
+
+    pub fn resume(&mut self) -> JsValue {
+        self.act(Action::Resume)
+    }
+
+    pub fn tick(&mut self) -> JsValue {
+        self.act(Action::Tick)
+    }
+
+    fn advance(&mut self) {
+        if let Some(elapsed) = self.lock.filter(|_| self.scene.check_grounded()) {
+            let elapsed = elapsed + self.interval();
+            if elapsed >= self.settings.lock_delay {
+                self.lock_figure();
+                return;
+            }
+            self.lock = Some(elapsed);
+        }
+
+        self.fall += self.gravity().rows_per_tick();
+        while self.fall >= 1.0 {
+            self.fall -= 1.0;
+            if self.scene.move_figure_y(1) {
+                self.fall = 0.0;
+            }
+        }
+        self.update_lock(false);
+    }
+}
+
+impl Game {
+    pub fn from_sequence(settings: Settings, kinds: Vec<u8>) -> Result<Game, String> {
+        if kinds.is_empty() {
+            return Err("sequence is empty".to_string());
+        }
+        let shapes = kinds.iter().map(|&kind| ShapeKind::try_from(kind));
+        let sequence = Sequence::new(shapes.collect::<Result<_, _>>()?);
+
+        let recording = Recording::new(settings, Some(kinds));
+        let scene = Scene::with_randomizer(&settings, Box::new(sequence));
+        Ok(Game::with_scene(scene, settings, recording))
+    }
+
+    // Every action is recorded, also the ones the game turns down, so that a
+    // replay goes through exactly the same calls. Ticks only count frames in
+    // the recording. Input and ticks only reach a running game, anything else
+    // is rejected and false is returned.
+    pub fn apply(&mut self, action: Action) -> bool {
+        self.recording.record(self.frame, action);
+        if action == Action::Tick {
+            self.frame += 1;
+        }
+
+        let allowed = match action {
+            Action::Start => self.status == GameStatus::Pending,
+            Action::Resume => self.status == GameStatus::Paused,
+            Action::Undo => matches!(self.status, GameStatus::InProgress | GameStatus::Over),
+            _ => self.status == GameStatus::InProgress,
+        };
+        if !allowed {
+            return self.reject();
+        }
+
+        match action {
+            Action::Start => {
+                self.status = GameStatus::InProgress;
+                self.spawned();
+                self.checkpoint();
+            }
+            Action::Pause => self.status = GameStatus::Paused,
+            Action::Resume => self.status = GameStatus::InProgress,
+            Action::Tick => self.advance(),
+            Action::MoveLeft => self.shift(-1),
+            Action::MoveRight => self.shift(1),
+            Action::MoveDown => self.soft_drop(),
+            Action::HardDrop => self.drop_figure(),
+            Action::Rotate => self.turn(Scene::rotate_figure),
+            Action::RotateCcw => self.turn(Scene::rotate_figure_ccw),
+            Action::Rotate180 => self.turn(Scene::rotate_figure_180),
+            Action::Hold => self.hold_figure(),
+            Action::Undo => return self.take_back(),
+            Action::Garbage { rows } => self.raise(rows),
+        }
+        true
+    }
+
+    pub fn get_state(&self) -> GameState {
+        let hidden = self.scene.hidden_rows();
         GameState {
-            blocks: self.scene.get_merged(),
+            width: self.scene.width(),
+            height: self.scene.height(),
+            spawn_rows: self.scene.spawn_rows(),
+            blocks: self.scene.get_merged().crop_top(hidden),
+            ghost: self.scene.get_ghost().crop_top(hidden),
             status: self.status,
+            over: self.over,
+            rejected: false,
+            score: self.score,
+            interval: self.interval(),
+            next: self.scene.get_queue(),
+            hold: self.scene.get_hold(),
         }
     }
 
-    pub fn start(&mut self) -> JsValue {
-        if self.status == GameStatus::Pending {
-            self.status = GameStatus::InProgress
+    pub fn get_frame(&self) -> u32 {
+        self.frame
+    }
+
+    pub fn get_status(&self) -> GameStatus {
+        self.status
+    }
+
+    pub fn get_scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    pub fn get_recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        self.events.drain(..).collect()
+    }
+
+    // Saved scenes, also the ones kept for undo, must not panic once played.
+    pub fn check(&self) -> Result<(), String> {
+        let scenes = self.history.iter().map(|(scene, _)| scene);
+        std::iter::once(&self.scene)
+            .chain(scenes)
+            .try_for_each(|scene| scene.check(&self.settings))
+    }
+}
+
+#[allow(deprecated)]
+fn to_js<T: Serialize>(value: &T) -> JsValue {
+    JsValue::from_serde(value).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::board::{SPAWN_ROWS, STANDARD_BUFFER};
+    use crate::models::randomizer::RandomizerKind;
+    use crate::models::score::Spin;
+
+    fn create_game() -> Game {
+        let mut settings = Settings::new();
+        settings.seed = 0;
+        let sequence = vec![ShapeKind::O as u8];
+        let mut game = Game::from_sequence(settings, sequence).unwrap();
+        game.status = GameStatus::InProgress;
+        game
+    }
+
+    fn land(game: &mut Game) {
+        while game.lock.is_none() {
+            game.soft_drop();
         }
-        self.to_js()
     }
 
-    pub fn tick(&mut self) -> JsValue {
-        self.move_down()
+    #[test]
+    fn check_shape_locks_after_delay() {
+        let mut game = create_game();
+        land(&mut game);
+        assert_eq!(game.interval(), frame_interval());
+
+        let ticks = game.settings.lock_delay.div_ceil(frame_interval());
+        for _ in 1..ticks {
+            game.advance();
+        }
+        let floor = STANDARD_BUFFER + STANDARD_HEIGHT - 2;
+        assert_eq!(game.scene.get_figure_y(), floor as i8);
+
+        game.advance();
+        let spawn = STANDARD_BUFFER - SPAWN_ROWS;
+        assert_eq!(game.scene.get_figure_y(), spawn as i8);
+        assert_eq!(game.lock, None);
+    }
+
+    #[test]
+    fn check_move_resets_lock_timer() {
+        let mut game = create_game();
+        land(&mut game);
+
+        game.advance();
+        game.advance();
+        assert_eq!(game.lock, Some(2 * frame_interval()));
+
+        game.shift(1);
+        assert_eq!(game.lock, Some(0));
+        assert_eq!(game.lock_resets, 1);
+    }
+
+    #[test]
+    fn check_lock_resets_are_limited() {
+        let mut game = create_game();
+        game.settings.lock_resets = 2;
+        land(&mut game);
+
+        for dx in [1, -1, 1].iter() {
+            game.advance();
+            game.shift(*dx);
+        }
+        assert_eq!(game.lock, Some(frame_interval()));
+        assert_eq!(game.lock_resets, 2);
+    }
+
+    #[test]
+    fn check_lifting_off_keeps_lock_timer() {
+        let mut settings = Settings::new();
+        settings.seed = 0;
+        settings.lock_resets = 2;
+        let mut game = Game::from_sequence(settings, vec![ShapeKind::T as u8]).unwrap();
+        game.status = GameStatus::InProgress;
+        land(&mut game);
+        game.drain_events();
+
+        for _ in 0..100 {
+            game.apply(Action::Rotate180);
+            for _ in 0..3 {
+                game.apply(Action::Tick);
+            }
+        }
+        let mut events = game.drain_events().into_iter();
+        assert!(events.any(|e| matches!(e, GameEvent::PieceLocked { .. })));
+    }
+
+    #[test]
+    fn check_block_out_ends_game() {
+        let mut game = create_game();
+        game.raise(STANDARD_HEIGHT - 1);
+        assert_eq!(game.status, GameStatus::InProgress);
+
+        game.drop_figure();
+        assert_eq!(game.over, Some(GameOver::BlockOut));
+    }
+
+    #[test]
+    fn check_lock_out_ends_game() {
+        let mut game = create_game();
+        game.raise(STANDARD_HEIGHT);
+        assert_eq!(game.status, GameStatus::InProgress);
+
+        game.drop_figure();
+        assert_eq!(game.over, Some(GameOver::LockOut));
+    }
+
+    #[test]
+    fn check_top_out_ends_game() {
+        let mut game = create_game();
+        game.raise(STANDARD_BUFFER + STANDARD_HEIGHT + 1);
+        assert_eq!(game.status, GameStatus::Over);
+        assert_eq!(game.over, Some(GameOver::TopOut));
+    }
+
+    #[test]
+    fn check_actions_need_running_game() {
+        let mut game = create_game();
+        game.status = GameStatus::Pending;
+        assert!(!game.apply(Action::MoveRight));
+        assert_eq!(
+            game.scene.get_figure_y(),
+            (STANDARD_BUFFER - SPAWN_ROWS) as i8
+        );
+
+        assert!(game.apply(Action::Start));
+        assert!(game.apply(Action::MoveDown));
+
+        game.apply(Action::Pause);
+        game.drain_events();
+        assert!(!game.apply(Action::Tick));
+        assert_eq!(game.drain_events(), vec![GameEvent::ActionRejected]);
+        assert_eq!(
+            game.scene.get_figure_y(),
+            (STANDARD_BUFFER - SPAWN_ROWS + 1) as i8
+        );
+
+        assert!(!game.apply(Action::Pause));
+        game.apply(Action::Resume);
+        assert_eq!(game.status, GameStatus::InProgress);
+    }
+
+    #[test]
+    fn check_lock_reports_events() {
+        let mut game = create_game();
+        game.raise(1);
+        game.drain_events();
+
+        game.drop_figure();
+        let events = game.drain_events();
+        assert_eq!(
+            events[0],
+            GameEvent::PieceLocked {
+                kind: ShapeKind::O,
+                spin: Spin::None
+            }
+        );
+        assert_eq!(events[1], GameEvent::PieceSpawned { kind: ShapeKind::O });
+        assert!(game.drain_events().is_empty());
+
+        game.status = GameStatus::Over;
+        game.apply(Action::MoveDown);
+        assert_eq!(game.drain_events(), vec![GameEvent::ActionRejected]);
+    }
+
+    #[test]
+    fn check_sequence_is_validated() {
+        let settings = Settings::new();
+        let error = Game::from_sequence(settings, vec![0, 7]).unwrap_err();
+        assert_eq!(error, "unknown shape 7");
+        assert!(Game::from_sequence(settings, vec![]).is_err());
+    }
+
+    #[test]
+    fn check_undo_takes_back_last_lock() {
+        let mut settings = Settings::new();
+        settings.seed = 0;
+        settings.undo = 2;
+        let mut game = Game::with_settings(settings);
+        game.apply(Action::Start);
+        game.apply(Action::MoveLeft);
+        game.apply(Action::HardDrop);
+        let second = game.get_state();
+        for _ in 0..2 {
+            game.apply(Action::HardDrop);
+        }
+
+        game.apply(Action::Undo);
+        game.apply(Action::Undo);
+        assert_eq!(game.get_state(), second);
+
+        assert!(!game.apply(Action::Undo));
+        assert_eq!(game.get_state().blocks, second.blocks);
+    }
+
+    #[test]
+    fn check_undo_after_game_over() {
+        let mut settings = Settings::new();
+        settings.seed = 0;
+        let mut game = Game::with_settings(settings);
+        game.apply(Action::Start);
+
+        let mut state = game.get_state();
+        while game.status != GameStatus::Over {
+            state = game.get_state();
+            game.apply(Action::HardDrop);
+        }
+
+        game.apply(Action::Undo);
+        assert_eq!(game.over, None);
+        assert_eq!(game.get_state(), state);
+    }
+
+    #[test]
+    fn check_undo_is_off_in_ranked_games() {
+        let mut settings = Settings::new();
+        settings.ranked = true;
+        let mut game = Game::with_settings(settings);
+        game.apply(Action::Start);
+        game.apply(Action::HardDrop);
+        let state = game.get_state();
+
+        assert!(!game.apply(Action::Undo));
+        assert_eq!(game.get_state().blocks, state.blocks);
+    }
+
+    #[test]
+    fn check_snapshot_restores_game() {
+        let mut settings = Settings::new();
+        settings.randomizer = RandomizerKind::History;
+        let mut game = Game::with_settings(settings);
+        let actions = [
+            Action::Rotate,
+            Action::MoveLeft,
+            Action::Hold,
+            Action::HardDrop,
+        ];
+        game.apply(Action::Start);
+        for action in actions.iter().cycle().take(10) {
+            game.apply(*action);
+        }
+        game.apply(Action::Garbage { rows: 2 });
+        game.apply(Action::Tick);
+        game.drain_events();
+
+        let mut restored = snapshot::load(&game.save()).unwrap();
+        assert_eq!(restored.get_state(), game.get_state());
+
+        for action in actions.iter().cycle().take(10) {
+            game.apply(*action);
+            restored.apply(*action);
+        }
+        game.apply(Action::Garbage { rows: 3 });
+        restored.apply(Action::Garbage { rows: 3 });
+        assert_eq!(restored.get_state(), game.get_state());
+        assert_eq!(restored.drain_events(), game.drain_events());
+        assert_eq!(game.status, GameStatus::InProgress);
+    }
+
+    #[test]
+    fn check_snapshot_version_is_checked() {
+        let game = Game::with_seed(0);
+        let version = format!("\"version\":{}", snapshot::SNAPSHOT_VERSION);
+        let json = game.save().replacen(&version, "\"version\":1", 1);
+        let error = snapshot::load(&json).unwrap_err();
+        assert_eq!(error, "unsupported snapshot version 1");
+
+        assert!(snapshot::load(&format!("{{{}}}", version)).is_err());
+        assert!(snapshot::load("not a game").is_err());
+    }
+
+    #[test]
+    fn check_snapshot_contents_are_checked() {
+        let json = create_game().save();
+        assert!(snapshot::load(&json).is_ok());
+
+        let corrupt = [
+            ("\"Sequence\":[[2],0]", "\"Sequence\":[[],0]"),
+            ("\"Sequence\":[[2],0]", "\"Sequence\":[[2],1]"),
+            ("[[0,0,0,0,0,0,0,0,0,0],", "[[0,0,0],"),
+            ("\"buffer\":20},", "\"buffer\":41},"),
+            ("\"queue\":[2,2,2,2,2]", "\"queue\":[]"),
+        ];
+        for (from, to) in corrupt.iter() {
+            let json = json.replacen(from, to, 1);
+            assert!(snapshot::load(&json).is_err(), "{}", to);
+        }
     }
 }
@@ -1,9 +1,23 @@
-use serde_repr::Serialize_repr;
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
-#[derive(Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum GameStatus {
     Pending = 0,
     InProgress = 1,
     Over = 2,
+    Paused = 3,
+}
+
+// What ended the game, named after the guideline top out conditions.
+#[allow(clippy::enum_variant_names)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum GameOver {
+    // A new piece spawned on top of the heap.
+    BlockOut = 0,
+    // A piece locked without a single block in the visible field.
+    LockOut = 1,
+    // Garbage pushed the heap out past the buffer.
+    TopOut = 2,
 }
@@ -0,0 +1,74 @@
+pub const FRAME_MS: f32 = 1000.0 / 60.0;
+
+pub fn frame_interval() -> u32 {
+    FRAME_MS.round() as u32
+}
+
+// Rows per frame at 60 fps for levels 1..=18, following the guideline curve
+// (0.8 - (level - 1) * 0.007) ^ (level - 1) seconds per row.
+static GRAVITY: [f32; 18] = [
+    0.01667, 0.02102, 0.02698, 0.03526, 0.04692, 0.06361, 0.08787, 0.1237, 0.17753, 0.2598,
+    0.38781, 0.59065, 0.91811, 1.45696, 2.36118, 3.9091, 6.61354, 11.43794,
+];
+
+// Everything past the table drops the piece to the floor within a single frame.
+pub const GRAVITY_20G: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gravity(f32);
+
+impl Gravity {
+    pub fn for_level(level: u32) -> Self {
+        let index = level.max(1) as usize - 1;
+        Gravity(*GRAVITY.get(index).unwrap_or(&GRAVITY_20G))
+    }
+
+    pub fn rows_per_frame(self) -> f32 {
+        self.0
+    }
+
+    // Slow gravity waits several frames between single-row drops, fast gravity
+    // ticks every frame and drops several rows at once.
+    pub fn rows_per_tick(self) -> f32 {
+        self.rows_per_frame().max(1.0)
+    }
+
+    pub fn interval(self) -> u32 {
+        (FRAME_MS / self.rows_per_frame().min(1.0)).round() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_first_level_drops_once_a_second() {
+        let gravity = Gravity::for_level(1);
+        assert_eq!(gravity.interval(), 1000);
+        assert_eq!(gravity.rows_per_tick(), 1.0);
+    }
+
+    #[test]
+    fn check_fast_levels_tick_every_frame() {
+        let gravity = Gravity::for_level(15);
+        assert_eq!(gravity.interval(), 17);
+        assert!(gravity.rows_per_tick() > 2.0);
+    }
+
+    #[test]
+    fn check_high_levels_are_20g() {
+        assert_eq!(Gravity::for_level(19).rows_per_frame(), GRAVITY_20G);
+        assert_eq!(Gravity::for_level(100).rows_per_frame(), GRAVITY_20G);
+    }
+
+    #[test]
+    fn check_gravity_grows_with_level() {
+        for level in 1..19 {
+            assert!(
+                Gravity::for_level(level).rows_per_frame()
+                    < Gravity::for_level(level + 1).rows_per_frame()
+            );
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use crate::models::board::BoardCell;
 use crate::models::dir::{HDir, VDir};
+use serde::{Deserialize, Serialize};
 
 pub type MatrixBody = Vec<Vec<BoardCell>>;
 type Indexes = [usize; 4];
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Matrix {
     body: MatrixBody,
 }
@@ -14,6 +15,9 @@ impl Matrix {
         Matrix { body }
     }
 
+    // The new top row is the `h_dir` column read from its `v_dir` end:
+    // (Bottom, Left) turns clockwise, (Top, Right) counter-clockwise,
+    // (Top, Left) and (Bottom, Right) mirror along the diagonals.
     pub fn rotate(&self, v_dir: VDir, h_dir: HDir) -> Self {
         let (height, width) = self.size();
         let v_range_vec = (0..height).collect::<Vec<_>>();
@@ -24,27 +28,19 @@ impl Matrix {
             acc
         };
 
-        match (v_dir, h_dir) {
-            (VDir::Bottom, HDir::Left) => {
-                let body = Matrix::create_indexes(
-                    h_range_vec,
-                    v_range_vec.into_iter().rev().collect::<Vec<_>>(),
-                )
-                .into_iter()
-                .fold(vec![vec![BoardCell::Empty; height]; width], read_backward);
-                Matrix { body }
-            }
-            (VDir::Top, HDir::Right) => {
-                let body = Matrix::create_indexes(
-                    h_range_vec.into_iter().rev().collect::<Vec<_>>(),
-                    v_range_vec,
-                )
-                .into_iter()
-                .fold(vec![vec![BoardCell::Empty; height]; width], read_backward);
-                Matrix { body }
-            }
-            _ => unimplemented!(),
-        }
+        let row_it = match h_dir {
+            HDir::Left => h_range_vec,
+            HDir::Right => h_range_vec.into_iter().rev().collect::<Vec<_>>(),
+        };
+        let col_it = match v_dir {
+            VDir::Top => v_range_vec,
+            VDir::Bottom => v_range_vec.into_iter().rev().collect::<Vec<_>>(),
+        };
+
+        let body = Matrix::create_indexes(row_it, col_it)
+            .into_iter()
+            .fold(vec![vec![BoardCell::Empty; height]; width], read_backward);
+        Matrix { body }
     }
 
     pub fn size(&self) -> (usize, usize) {
@@ -72,7 +68,7 @@ impl Matrix {
         (bottom, right): (usize, usize),
     ) -> MatrixBody {
         self.body[top..bottom]
-            .into_iter()
+            .iter()
             .map(|row| row[left..right].to_vec())
             .collect()
     }
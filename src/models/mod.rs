@@ -1,7 +1,16 @@
+pub mod action;
 pub mod board;
 pub mod dir;
+pub mod event;
 pub mod game;
 pub mod game_status;
+pub mod gravity;
 pub mod matrix;
+pub mod randomizer;
+pub mod replay;
+pub mod rotation;
 pub mod scene;
+pub mod score;
+pub mod settings;
 pub mod shape;
+pub mod snapshot;
@@ -0,0 +1,273 @@
+use crate::models::shape::{ShapeKind, SHAPE_KINDS};
+use rand::seq::SliceRandom;
+use rand::RngCore;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use wasm_bindgen::prelude::*;
+
+pub trait Randomizer: Debug {
+    fn next(&mut self, rng: &mut dyn RngCore) -> ShapeKind;
+    fn state(&self) -> RandomizerState;
+}
+
+// Everything a randomizer has dealt from so far, enough to pick up where it
+// left off after a save.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum RandomizerState {
+    Random,
+    Bag(Vec<ShapeKind>),
+    History(VecDeque<ShapeKind>, bool),
+    Sequence(Vec<ShapeKind>, usize),
+}
+
+impl RandomizerState {
+    pub fn restore(self) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerState::Random => Box::new(PureRandom),
+            RandomizerState::Bag(bag) => Box::new(Bag { bag }),
+            RandomizerState::History(history, first) => Box::new(History { history, first }),
+            RandomizerState::Sequence(kinds, position) => Box::new(Sequence { kinds, position }),
+        }
+    }
+
+    // A state that would make the next draw panic, only a corrupt save has one.
+    pub fn check(&self) -> Result<(), String> {
+        match self {
+            RandomizerState::History(history, _) if history.len() != HISTORY_SIZE => {
+                Err(format!("history holds {} shapes", history.len()))
+            }
+            RandomizerState::Sequence(kinds, _) if kinds.is_empty() => {
+                Err("sequence is empty".to_string())
+            }
+            RandomizerState::Sequence(kinds, position) if *position >= kinds.len() => {
+                Err(format!("sequence position {} is out of range", position))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.state().restore()
+    }
+}
+
+impl Serialize for Box<dyn Randomizer> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.state().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<dyn Randomizer> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RandomizerState::deserialize(deserializer).map(RandomizerState::restore)
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RandomizerKind {
+    Random = 0,
+    Bag = 1,
+    History = 2,
+}
+
+impl RandomizerKind {
+    pub fn create(self) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::Random => Box::new(PureRandom),
+            RandomizerKind::Bag => Box::new(Bag::new()),
+            RandomizerKind::History => Box::new(History::new()),
+        }
+    }
+}
+
+// Every shape is equally likely on every draw.
+#[derive(Debug)]
+pub struct PureRandom;
+
+impl Randomizer for PureRandom {
+    fn next(&mut self, rng: &mut dyn RngCore) -> ShapeKind {
+        *SHAPE_KINDS.choose(rng).unwrap()
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::Random
+    }
+}
+
+// Deals all seven shapes in a shuffled order before refilling.
+#[derive(Debug)]
+pub struct Bag {
+    bag: Vec<ShapeKind>,
+}
+
+impl Bag {
+    pub fn new() -> Self {
+        Bag { bag: vec![] }
+    }
+}
+
+impl Default for Bag {
+    fn default() -> Self {
+        Bag::new()
+    }
+}
+
+impl Randomizer for Bag {
+    fn next(&mut self, rng: &mut dyn RngCore) -> ShapeKind {
+        if self.bag.is_empty() {
+            self.bag = SHAPE_KINDS.to_vec();
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap()
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::Bag(self.bag.clone())
+    }
+}
+
+const HISTORY_SIZE: usize = 4;
+const HISTORY_ROLLS: usize = 6;
+
+// TGM style: rerolls a few times when the shape is among the last four dealt,
+// and never opens with a shape that forces an overhang.
+#[derive(Debug)]
+pub struct History {
+    history: VecDeque<ShapeKind>,
+    first: bool,
+}
+
+impl History {
+    pub fn new() -> Self {
+        History {
+            history: VecDeque::from(vec![ShapeKind::Z, ShapeKind::S, ShapeKind::S, ShapeKind::Z]),
+            first: true,
+        }
+    }
+
+    fn roll(&self, rng: &mut dyn RngCore) -> ShapeKind {
+        if self.first {
+            let kinds = [ShapeKind::I, ShapeKind::J, ShapeKind::L, ShapeKind::T];
+            return *kinds.choose(rng).unwrap();
+        }
+
+        let mut kind = *SHAPE_KINDS.choose(rng).unwrap();
+        for _ in 1..HISTORY_ROLLS {
+            if !self.history.contains(&kind) {
+                break;
+            }
+            kind = *SHAPE_KINDS.choose(rng).unwrap();
+        }
+        kind
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History::new()
+    }
+}
+
+impl Randomizer for History {
+    fn next(&mut self, rng: &mut dyn RngCore) -> ShapeKind {
+        let kind = self.roll(rng);
+        self.first = false;
+        self.history.pop_front();
+        self.history.push_back(kind);
+        debug_assert_eq!(self.history.len(), HISTORY_SIZE);
+        kind
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::History(self.history.clone(), self.first)
+    }
+}
+
+// Replays a fixed script of shapes, starting over once it runs out.
+#[derive(Debug)]
+pub struct Sequence {
+    kinds: Vec<ShapeKind>,
+    position: usize,
+}
+
+impl Sequence {
+    pub fn new(kinds: Vec<ShapeKind>) -> Self {
+        assert!(!kinds.is_empty(), "sequence is empty");
+        Sequence { kinds, position: 0 }
+    }
+}
+
+impl Randomizer for Sequence {
+    fn next(&mut self, _rng: &mut dyn RngCore) -> ShapeKind {
+        let kind = self.kinds[self.position];
+        self.position = (self.position + 1) % self.kinds.len();
+        kind
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::Sequence(self.kinds.clone(), self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn check_bag_deals_every_shape_once() {
+        let mut bag = Bag::new();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..3 {
+            let mut dealt = (0..7).map(|_| bag.next(&mut rng)).collect::<Vec<_>>();
+            dealt.sort_by_key(|&kind| kind as u8);
+            assert_eq!(dealt, SHAPE_KINDS.to_vec());
+        }
+    }
+
+    #[test]
+    fn check_history_never_opens_with_overhang() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..50 {
+            let first = History::new().next(&mut rng);
+            assert!(![ShapeKind::S, ShapeKind::Z, ShapeKind::O].contains(&first));
+        }
+    }
+
+    #[test]
+    fn check_restored_bag_deals_the_rest() {
+        let mut bag = Bag::new();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let dealt = (0..3).map(|_| bag.next(&mut rng)).collect::<Vec<_>>();
+
+        let mut restored = bag.state().restore();
+        let mut rest = (0..4).map(|_| restored.next(&mut rng)).collect::<Vec<_>>();
+        rest.extend(dealt);
+        rest.sort_by_key(|&kind| kind as u8);
+        assert_eq!(rest, SHAPE_KINDS.to_vec());
+    }
+
+    #[test]
+    fn check_sequence_repeats() {
+        let mut sequence = Sequence::new(vec![ShapeKind::I, ShapeKind::O]);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let dealt = (0..5).map(|_| sequence.next(&mut rng)).collect::<Vec<_>>();
+        assert_eq!(
+            dealt,
+            vec![
+                ShapeKind::I,
+                ShapeKind::O,
+                ShapeKind::I,
+                ShapeKind::O,
+                ShapeKind::I
+            ]
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use super::action::Action;
+use super::game::Game;
+use super::settings::Settings;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    // Ticks the game had seen when the action came in.
+    pub frame: u32,
+    pub action: Action,
+}
+
+// Settings carry the seed, so they are all it takes to deal the same pieces
+// again, unless the game was played on a fixed sequence. Ticks come every
+// frame, so only their count is kept and replays put them back in between
+// the steps by frame number.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    pub settings: Settings,
+    pub sequence: Option<Vec<u8>>,
+    pub steps: Vec<Step>,
+    pub frames: u32,
+}
+
+impl Recording {
+    pub fn new(settings: Settings, sequence: Option<Vec<u8>>) -> Self {
+        Recording {
+            settings,
+            sequence,
+            steps: vec![],
+            frames: 0,
+        }
+    }
+
+    pub fn record(&mut self, frame: u32, action: Action) {
+        match action {
+            Action::Tick => self.frames = frame + 1,
+            _ => self.steps.push(Step { frame, action }),
+        }
+    }
+
+    pub fn create_game(&self) -> Result<Game, String> {
+        match &self.sequence {
+            Some(kinds) => Game::from_sequence(self.settings, kinds.clone()),
+            None => Ok(Game::with_settings(self.settings)),
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct Replay {
+    recording: Recording,
+    game: Game,
+    position: usize,
+}
+
+#[wasm_bindgen]
+impl Replay {
+    // Throws on the JS side when the recording does not parse.
+    #[allow(deprecated)]
+    pub fn from_js(recording: JsValue) -> Result<Replay, JsValue> {
+        let recording = recording
+            .into_serde()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Replay::new(recording).map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn len(&self) -> usize {
+        self.recording.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recording.steps.is_empty()
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn state(&self) -> JsValue {
+        self.game.to_js()
+    }
+
+    pub fn step(&mut self) -> JsValue {
+        self.forward();
+        self.state()
+    }
+
+    pub fn seek(&mut self, position: usize) -> JsValue {
+        self.go_to(position);
+        self.state()
+    }
+
+    pub fn seek_frame(&mut self, frame: u32) -> JsValue {
+        self.go_to_frame(frame);
+        self.state()
+    }
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Result<Self, String> {
+        let game = recording.create_game()?;
+        Ok(Replay {
+            recording,
+            game,
+            position: 0,
+        })
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    // Plays the ticks up to the next recorded action and the action itself,
+    // or the ticks after the last one. False once the recording is over.
+    // Nobody reads the events of a replayed game, they are dropped as it goes.
+    pub fn forward(&mut self) -> bool {
+        match self.recording.steps.get(self.position).copied() {
+            Some(step) => {
+                self.tick_to(step.frame);
+                self.game.apply(step.action);
+                self.game.drain_events();
+                self.position += 1;
+                true
+            }
+            None if self.game.get_frame() < self.recording.frames => {
+                self.tick_to(self.recording.frames);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn tick_to(&mut self, frame: u32) {
+        while self.game.get_frame() < frame {
+            self.game.apply(Action::Tick);
+            self.game.drain_events();
+        }
+    }
+
+    // Games only run forward, so going back starts over from the first step.
+    fn rewind(&mut self) {
+        self.game = self
+            .recording
+            .create_game()
+            .expect("checked in Replay::new");
+        self.position = 0;
+    }
+
+    // Seeking to the end also plays the ticks after the last action.
+    pub fn go_to(&mut self, position: usize) {
+        if position < self.position {
+            self.rewind();
+        }
+        while self.position < position && self.forward() {}
+        if position >= self.len() {
+            while self.forward() {}
+        }
+    }
+
+    // Stops right before the first action of a later frame.
+    pub fn go_to_frame(&mut self, frame: u32) {
+        let frame = frame.min(self.recording.frames);
+        let steps = &self.recording.steps;
+        let position = steps.iter().take_while(|s| s.frame <= frame).count();
+        if position < self.position || self.game.get_frame() > frame {
+            self.rewind();
+        }
+        while self.position < position && self.forward() {}
+        self.tick_to(frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play() -> Game {
+        let mut settings = Settings::new();
+        settings.seed = 42;
+        let mut game = Game::with_settings(settings);
+
+        game.apply(Action::Start);
+        for i in 0..200 {
+            let action = match i % 7 {
+                0 => Action::MoveLeft,
+                1 => Action::Rotate,
+                3 => Action::HardDrop,
+                5 => Action::MoveRight,
+                _ => Action::Tick,
+            };
+            game.apply(action);
+        }
+        game.apply(Action::Tick);
+        game.apply(Action::Tick);
+        game
+    }
+
+    #[test]
+    fn check_replay_rebuilds_game() {
+        let game = play();
+        let recording = game.get_recording();
+        assert!(recording.steps.iter().all(|s| s.action != Action::Tick));
+        assert_eq!(recording.frames, 87);
+
+        let mut replay = Replay::new(recording.clone()).unwrap();
+        assert_eq!(replay.len(), 116);
+
+        while replay.forward() {}
+        assert_eq!(replay.game().get_state(), game.get_state());
+        assert!(replay.game.drain_events().is_empty());
+    }
+
+    #[test]
+    fn check_recording_keeps_large_seeds() {
+        let mut settings = Settings::new();
+        settings.seed = (1 << 53) + 1;
+        let recording = Recording::new(settings, None);
+
+        let json = serde_json::to_string(&recording).unwrap();
+        assert!(json.contains("\"seed\":\"9007199254740993\""));
+        let parsed: Recording = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.settings.seed, settings.seed);
+    }
+
+    #[test]
+    fn check_replay_seeks_both_ways() {
+        let game = play();
+        let mut replay = Replay::new(game.get_recording().clone()).unwrap();
+
+        replay.go_to(60);
+        let state = replay.game().get_state();
+        replay.go_to(replay.len());
+        assert_eq!(replay.game().get_state(), game.get_state());
+        replay.go_to(60);
+        assert_eq!(replay.position(), 60);
+        assert_eq!(replay.game().get_state(), state);
+
+        replay.go_to_frame(0);
+        assert_eq!(replay.position(), 3);
+        replay.go_to_frame(10);
+        assert_eq!(replay.game().get_frame(), 10);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::models::shape::ShapeKind;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Rotation {
+    Spawn = 0,
+    Right = 1,
+    Reverse = 2,
+    Left = 3,
+}
+
+impl From<u8> for Rotation {
+    fn from(i: u8) -> Self {
+        match i % 4 {
+            0 => Rotation::Spawn,
+            1 => Rotation::Right,
+            2 => Rotation::Reverse,
+            _ => Rotation::Left,
+        }
+    }
+}
+
+impl Rotation {
+    pub fn cw(self) -> Self {
+        Rotation::from(self as u8 + 1)
+    }
+
+    pub fn ccw(self) -> Self {
+        Rotation::from(self as u8 + 3)
+    }
+}
+
+pub type Kick = (i8, i8);
+
+// SRS wall kicks as (x, y) offsets with y pointing up, in the order they are
+// tried. Rows are 0->R, R->0, R->2, 2->R, 2->L, L->2, L->0, 0->L.
+static JLSTZ_KICKS: [[Kick; 5]; 8] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+];
+
+static I_KICKS: [[Kick; 5]; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+];
+
+// Half turns are not part of SRS, these follow the common SRS+ extension.
+// Rows are 0->2, R->L, 2->0, L->R.
+static HALF_TURN_KICKS: [[Kick; 6]; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+];
+
+static NO_KICKS: [Kick; 1] = [(0, 0)];
+
+pub fn kicks(kind: ShapeKind, from: Rotation, to: Rotation) -> &'static [Kick] {
+    if kind == ShapeKind::O || to == from {
+        return &NO_KICKS;
+    }
+
+    if to == from.cw().cw() {
+        return &HALF_TURN_KICKS[from as usize];
+    }
+
+    let row = if to == from.cw() {
+        2 * from as usize
+    } else {
+        (2 * from as usize + 7) % 8
+    };
+
+    match kind {
+        ShapeKind::I => &I_KICKS[row],
+        _ => &JLSTZ_KICKS[row],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_rotation_wraps() {
+        assert_eq!(Rotation::Left.cw(), Rotation::Spawn);
+        assert_eq!(Rotation::Spawn.ccw(), Rotation::Left);
+        assert_eq!(Rotation::Right.cw().cw(), Rotation::Left);
+    }
+
+    #[test]
+    fn check_kicks_lookup() {
+        assert_eq!(
+            kicks(ShapeKind::T, Rotation::Spawn, Rotation::Right)[1],
+            (-1, 0)
+        );
+        assert_eq!(
+            kicks(ShapeKind::T, Rotation::Spawn, Rotation::Left)[1],
+            (1, 0)
+        );
+        assert_eq!(
+            kicks(ShapeKind::I, Rotation::Right, Rotation::Spawn)[1],
+            (2, 0)
+        );
+        assert_eq!(
+            kicks(ShapeKind::O, Rotation::Spawn, Rotation::Right).len(),
+            1
+        );
+        assert_eq!(
+            kicks(ShapeKind::T, Rotation::Right, Rotation::Left)[1],
+            (1, 0)
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+use serde_repr::Serialize_repr;
+
+pub const LINES_PER_LEVEL: u32 = 10;
+pub const HARD_DROP_POINTS: u32 = 2;
+pub const COMBO_POINTS: u32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineClear {
+    Single,
+    Double,
+    Triple,
+    Tetris,
+}
+
+impl LineClear {
+    // Standard pieces clear four lines at most, anything more still counts as
+    // a tetris.
+    pub fn from_count(lines: usize) -> Option<Self> {
+        match lines {
+            0 => None,
+            1 => Some(LineClear::Single),
+            2 => Some(LineClear::Double),
+            3 => Some(LineClear::Triple),
+            _ => Some(LineClear::Tetris),
+        }
+    }
+
+    pub fn points(self) -> u32 {
+        match self {
+            LineClear::Single => 100,
+            LineClear::Double => 300,
+            LineClear::Triple => 500,
+            LineClear::Tetris => 800,
+        }
+    }
+
+    pub fn perfect_clear_points(self, back_to_back: bool) -> u32 {
+        match self {
+            LineClear::Single => 800,
+            LineClear::Double => 1200,
+            LineClear::Triple => 1800,
+            LineClear::Tetris if back_to_back => 3200,
+            LineClear::Tetris => 2000,
+        }
+    }
+}
+
+#[derive(Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Spin {
+    None = 0,
+    Mini = 1,
+    Full = 2,
+}
+
+impl Spin {
+    pub fn points(self, lines: usize) -> u32 {
+        match (self, lines) {
+            (Spin::None, _) => LineClear::from_count(lines).map_or(0, LineClear::points),
+            (Spin::Mini, 0) => 100,
+            (Spin::Mini, 1) => 200,
+            (Spin::Mini, _) => 400,
+            (Spin::Full, 0) => 400,
+            (Spin::Full, 1) => 800,
+            (Spin::Full, 2) => 1200,
+            (Spin::Full, _) => 1600,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockResult {
+    pub lines: usize,
+    pub spin: Spin,
+    pub perfect_clear: bool,
+}
+
+impl LockResult {
+    pub fn new(lines: usize, spin: Spin) -> Self {
+        LockResult {
+            lines,
+            spin,
+            perfect_clear: false,
+        }
+    }
+
+    // Tetrises and spins that clear lines keep the back-to-back chain going.
+    pub fn is_difficult(&self) -> bool {
+        self.lines >= 4 || (self.lines > 0 && self.spin != Spin::None)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub points: u32,
+    pub lines: u32,
+    pub level: u32,
+    pub singles: u32,
+    pub doubles: u32,
+    pub triples: u32,
+    pub tetrises: u32,
+    pub tspins: u32,
+    pub combo: u32,
+    pub back_to_back: bool,
+    pub perfect_clears: u32,
+}
+
+impl Score {
+    pub fn new() -> Self {
+        Score {
+            points: 0,
+            lines: 0,
+            level: 1,
+            singles: 0,
+            doubles: 0,
+            triples: 0,
+            tetrises: 0,
+            tspins: 0,
+            combo: 0,
+            back_to_back: false,
+            perfect_clears: 0,
+        }
+    }
+
+    pub fn credit(&mut self, lock: &LockResult) {
+        let LockResult {
+            lines,
+            spin,
+            perfect_clear,
+        } = *lock;
+
+        if spin != Spin::None {
+            self.tspins += 1;
+        }
+
+        match LineClear::from_count(lines) {
+            Some(LineClear::Single) => self.singles += 1,
+            Some(LineClear::Double) => self.doubles += 1,
+            Some(LineClear::Triple) => self.triples += 1,
+            Some(LineClear::Tetris) => self.tetrises += 1,
+            None => {}
+        }
+
+        let mut points = spin.points(lines);
+        let chained = lock.is_difficult() && self.back_to_back;
+
+        // Spins without lines neither start nor break a back-to-back chain,
+        // any other clear that is not difficult breaks it.
+        if lock.is_difficult() {
+            if chained {
+                points = points * 3 / 2;
+            }
+            self.back_to_back = true;
+        } else if lines > 0 {
+            self.back_to_back = false;
+        }
+
+        if let (true, Some(clear)) = (perfect_clear, LineClear::from_count(lines)) {
+            self.perfect_clears += 1;
+            points += clear.perfect_clear_points(chained);
+        }
+
+        // Combo counts locks in a row that clear lines, the bonus starts with
+        // the second one.
+        if lines > 0 {
+            points += COMBO_POINTS * self.combo;
+            self.combo += 1;
+        } else {
+            self.combo = 0;
+        }
+
+        self.points += points * self.level;
+        self.lines += lines as u32;
+        self.level = self.lines / LINES_PER_LEVEL + 1;
+    }
+
+    pub fn credit_hard_drop(&mut self, rows: u8) {
+        self.points += HARD_DROP_POINTS * rows as u32;
+    }
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        Score::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_clear_points_scale_with_level() {
+        let mut score = Score::new();
+        score.credit(&LockResult::new(1, Spin::None));
+        assert_eq!(score.points, 100);
+
+        score.credit(&LockResult::new(0, Spin::None));
+        score.level = 3;
+        score.credit(&LockResult::new(4, Spin::None));
+        assert_eq!(score.points, 100 + 800 * 3);
+        assert_eq!(score.singles, 1);
+        assert_eq!(score.tetrises, 1);
+    }
+
+    #[test]
+    fn check_more_than_four_lines_count_as_tetris() {
+        assert_eq!(LineClear::from_count(6), Some(LineClear::Tetris));
+
+        let mut score = Score::new();
+        score.credit(&LockResult::new(5, Spin::None));
+        assert_eq!(score.tetrises, 1);
+        assert_eq!(score.lines, 5);
+    }
+
+    #[test]
+    fn check_empty_credit_is_ignored() {
+        let mut score = Score::new();
+        score.credit(&LockResult::new(0, Spin::None));
+        assert_eq!(score, Score::new());
+    }
+
+    #[test]
+    fn check_level_advances_every_n_lines() {
+        let mut score = Score::new();
+        for _ in 0..2 {
+            score.credit(&LockResult::new(4, Spin::None));
+        }
+        assert_eq!(score.level, 1);
+
+        score.credit(&LockResult::new(2, Spin::None));
+        assert_eq!(score.lines, LINES_PER_LEVEL);
+        assert_eq!(score.level, 2);
+    }
+
+    #[test]
+    fn check_hard_drop_points_per_row() {
+        let mut score = Score::new();
+        score.credit_hard_drop(15);
+        assert_eq!(score.points, 30);
+        assert_eq!(score.lines, 0);
+    }
+
+    #[test]
+    fn check_tspins_score_more_than_plain_clears() {
+        let mut score = Score::new();
+        score.credit(&LockResult::new(2, Spin::Full));
+        assert_eq!(score.points, 1200);
+        assert_eq!(score.doubles, 1);
+
+        score.credit(&LockResult::new(0, Spin::Mini));
+        assert_eq!(score.points, 1300);
+        assert_eq!(score.tspins, 2);
+        assert_eq!(score.lines, 2);
+    }
+
+    #[test]
+    fn check_combo_grows_until_a_lock_misses() {
+        let mut score = Score::new();
+        for _ in 0..3 {
+            score.credit(&LockResult::new(1, Spin::None));
+        }
+        assert_eq!(score.combo, 3);
+        assert_eq!(score.points, 100 + 150 + 200);
+
+        score.credit(&LockResult::new(0, Spin::None));
+        assert_eq!(score.combo, 0);
+    }
+
+    #[test]
+    fn check_back_to_back_difficult_clears() {
+        let mut score = Score::new();
+        score.credit(&LockResult::new(4, Spin::None));
+        score.credit(&LockResult::new(0, Spin::None));
+        assert!(score.back_to_back);
+
+        score.credit(&LockResult::new(0, Spin::Full));
+        score.credit(&LockResult::new(4, Spin::None));
+        assert_eq!(score.points, 800 + 400 + 1200);
+
+        score.credit(&LockResult::new(2, Spin::Full));
+        assert_eq!(score.points, 800 + 400 + 1200 + 1800 + 50);
+
+        score.credit(&LockResult::new(1, Spin::None));
+        assert!(!score.back_to_back);
+    }
+
+    #[test]
+    fn check_perfect_clear_bonus() {
+        let mut score = Score::new();
+        let mut lock = LockResult::new(4, Spin::None);
+        lock.perfect_clear = true;
+        score.credit(&lock);
+        assert_eq!(score.points, 800 + 2000);
+        assert_eq!(score.perfect_clears, 1);
+
+        score.credit(&LockResult::new(0, Spin::None));
+        score.credit(&lock);
+        assert_eq!(score.points, 2800 + 1200 + 3200);
+    }
+}
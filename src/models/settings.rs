@@ -0,0 +1,95 @@
+use super::board::{STANDARD_BUFFER, STANDARD_HEIGHT, STANDARD_WIDTH};
+use super::randomizer::RandomizerKind;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+pub const MIN_PREVIEW: usize = 1;
+pub const MAX_PREVIEW: usize = 6;
+// Narrow enough for the I piece to lie flat, small enough for i8 positions.
+pub const MIN_SIZE: usize = 4;
+pub const MAX_SIZE: usize = 40;
+pub const MAX_UNDO: usize = 100;
+
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub preview: usize,
+    pub randomizer: RandomizerKind,
+    #[serde(with = "seed_string")]
+    pub seed: u64,
+    pub lock_delay: u32,
+    pub lock_resets: u32,
+    pub width: usize,
+    pub height: usize,
+    pub buffer: usize,
+    // Locked pieces that can be taken back, ranked games never allow it.
+    pub undo: usize,
+    pub ranked: bool,
+}
+
+#[wasm_bindgen]
+impl Settings {
+    pub fn new() -> Self {
+        Settings {
+            preview: 5,
+            randomizer: RandomizerKind::Bag,
+            seed: rand::thread_rng().gen(),
+            lock_delay: 500,
+            lock_resets: 15,
+            width: STANDARD_WIDTH,
+            height: STANDARD_HEIGHT,
+            buffer: STANDARD_BUFFER,
+            undo: 10,
+            ranked: false,
+        }
+    }
+}
+
+// Seeds use the full u64 range, JS numbers would round most of them.
+mod seed_string {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(seed)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings::new()
+    }
+}
+
+impl Settings {
+    pub fn preview_len(&self) -> usize {
+        self.preview.clamp(MIN_PREVIEW, MAX_PREVIEW)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width.clamp(MIN_SIZE, MAX_SIZE)
+    }
+
+    pub fn height(&self) -> usize {
+        self.height.clamp(MIN_SIZE, MAX_SIZE)
+    }
+
+    pub fn buffer(&self) -> usize {
+        self.buffer.min(MAX_SIZE)
+    }
+
+    pub fn undo_depth(&self) -> usize {
+        if self.ranked {
+            0
+        } else {
+            self.undo.min(MAX_UNDO)
+        }
+    }
+}
@@ -1,18 +1,23 @@
 use crate::models::board::BoardCell;
-use crate::models::dir::{HDir, VDir};
+#[cfg(test)]
+use crate::models::board::STANDARD_WIDTH;
+use crate::models::dir::{HDir, Turn, VDir};
 use crate::models::matrix::{Matrix, MatrixBody};
-use rand::seq::SliceRandom;
+use crate::models::rotation::Rotation;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::convert::TryFrom;
 use std::fmt::Debug;
 
 // use super::matrix::MatrixBody;
 
-static SHAPES: [&'static [&'static [BoardCell]]; 7] = [
+static SHAPES: [&[&[BoardCell]]; 7] = [
     // [0, 1, 0],
     // [1, 1, 1],
     // [0, 0, 0],
     &[
-        &[BoardCell::Empty, BoardCell::Filled, BoardCell::Empty],
-        &[BoardCell::Filled, BoardCell::Filled, BoardCell::Filled],
+        &[BoardCell::Empty, BoardCell::T, BoardCell::Empty],
+        &[BoardCell::T, BoardCell::T, BoardCell::T],
         &[BoardCell::Empty, BoardCell::Empty, BoardCell::Empty],
     ],
     // [0, 0, 0, 0],
@@ -26,12 +31,7 @@ static SHAPES: [&'static [&'static [BoardCell]]; 7] = [
             BoardCell::Empty,
             BoardCell::Empty,
         ],
-        &[
-            BoardCell::Filled,
-            BoardCell::Filled,
-            BoardCell::Filled,
-            BoardCell::Filled,
-        ],
+        &[BoardCell::I, BoardCell::I, BoardCell::I, BoardCell::I],
         &[
             BoardCell::Empty,
             BoardCell::Empty,
@@ -47,40 +47,37 @@ static SHAPES: [&'static [&'static [BoardCell]]; 7] = [
     ],
     // [1, 1],
     // [1, 1],
-    &[
-        &[BoardCell::Filled, BoardCell::Filled],
-        &[BoardCell::Filled, BoardCell::Filled],
-    ],
+    &[&[BoardCell::O, BoardCell::O], &[BoardCell::O, BoardCell::O]],
     // [1, 1, 0],
     // [0, 1, 1],
     // [0, 0, 0],
     &[
-        &[BoardCell::Filled, BoardCell::Filled, BoardCell::Empty],
-        &[BoardCell::Empty, BoardCell::Filled, BoardCell::Filled],
+        &[BoardCell::Z, BoardCell::Z, BoardCell::Empty],
+        &[BoardCell::Empty, BoardCell::Z, BoardCell::Z],
         &[BoardCell::Empty, BoardCell::Empty, BoardCell::Empty],
     ],
     // [0, 1, 1],
     // [1, 1, 0],
     // [0, 0, 0],
     &[
-        &[BoardCell::Empty, BoardCell::Filled, BoardCell::Filled],
-        &[BoardCell::Filled, BoardCell::Filled, BoardCell::Empty],
+        &[BoardCell::Empty, BoardCell::S, BoardCell::S],
+        &[BoardCell::S, BoardCell::S, BoardCell::Empty],
         &[BoardCell::Empty, BoardCell::Empty, BoardCell::Empty],
     ],
     // [1, 0, 0],
     // [1, 1, 1],
     // [0, 0, 0],
     &[
-        &[BoardCell::Filled, BoardCell::Empty, BoardCell::Empty],
-        &[BoardCell::Filled, BoardCell::Filled, BoardCell::Filled],
+        &[BoardCell::J, BoardCell::Empty, BoardCell::Empty],
+        &[BoardCell::J, BoardCell::J, BoardCell::J],
         &[BoardCell::Empty, BoardCell::Empty, BoardCell::Empty],
     ],
     // [0, 0, 1],
     // [1, 1, 1],
     // [0, 0, 0],
     &[
-        &[BoardCell::Empty, BoardCell::Empty, BoardCell::Filled],
-        &[BoardCell::Filled, BoardCell::Filled, BoardCell::Filled],
+        &[BoardCell::Empty, BoardCell::Empty, BoardCell::L],
+        &[BoardCell::L, BoardCell::L, BoardCell::L],
         &[BoardCell::Empty, BoardCell::Empty, BoardCell::Empty],
     ],
 ];
@@ -98,52 +95,94 @@ static SHAPES: [&'static [&'static [BoardCell]]; 7] = [
 // type RectMatrix<const ROWS: usize, const COLS: usize> = [[u8; COLS]; ROWS];
 // type SquareMatrix<const SIZE: usize> = RectMatrix<SIZE, SIZE>;
 
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ShapeKind {
+    T = 0,
+    I = 1,
+    O = 2,
+    Z = 3,
+    S = 4,
+    J = 5,
+    L = 6,
+}
+
+pub static SHAPE_KINDS: [ShapeKind; 7] = [
+    ShapeKind::T,
+    ShapeKind::I,
+    ShapeKind::O,
+    ShapeKind::Z,
+    ShapeKind::S,
+    ShapeKind::J,
+    ShapeKind::L,
+];
+
+impl TryFrom<u8> for ShapeKind {
+    type Error = String;
+
+    fn try_from(i: u8) -> Result<Self, Self::Error> {
+        SHAPE_KINDS
+            .get(i as usize)
+            .copied()
+            .ok_or_else(|| format!("unknown shape {}", i))
+    }
+}
+
+impl ShapeKind {
+    pub fn body(self) -> MatrixBody {
+        SHAPES[self as usize]
+            .iter()
+            .map(|arr| arr.to_vec())
+            .collect()
+    }
+}
+
 pub trait Transform {
     fn move_x(&mut self, dx: i8);
     fn move_y(&mut self, dy: i8);
-    fn rotate(&mut self, v_dir: VDir, h_dir: HDir);
+    fn rotate(&mut self, turn: Turn);
+
+    fn rotate_cw(&mut self) {
+        self.rotate(Turn::Cw);
+    }
+
+    fn rotate_ccw(&mut self) {
+        self.rotate(Turn::Ccw);
+    }
+
+    fn rotate_180(&mut self) {
+        self.rotate_cw();
+        self.rotate_cw();
+    }
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Shape {
+    pub kind: ShapeKind,
     pub x: i8,
     pub y: i8,
+    pub rotation: Rotation,
     pub matrix: Matrix,
 }
 
 impl Shape {
-    pub fn get_named(name: &str) -> &[&[BoardCell]] {
-        match name {
-            "shape0" => SHAPES[0],
-            "shape1" => SHAPES[1],
-            "shape2" => SHAPES[2],
-            "shape3" => SHAPES[3],
-            "shape4" => SHAPES[4],
-            "shape5" => SHAPES[5],
-            "shape6" => SHAPES[6],
-            _ => panic!("Unknown shape"),
-        }
-    }
-
-    pub fn clone(matrix: &[&[BoardCell]]) -> MatrixBody {
-        matrix.iter().map(|arr| arr.to_vec()).collect()
+    #[cfg(test)]
+    pub fn new(kind: ShapeKind) -> Self {
+        Shape::spawn(kind, STANDARD_WIDTH)
     }
 
-    pub fn new(shape: MatrixBody) -> Self {
-        let x = 3;
+    // Centered, leaning left on even widths.
+    pub fn spawn(kind: ShapeKind, board_width: usize) -> Self {
+        let x = (board_width as i8 - 3) / 2;
         let y = 0;
         Shape {
+            kind,
             x,
             y,
-            matrix: Matrix::new(shape),
+            rotation: Rotation::Spawn,
+            matrix: Matrix::new(kind.body()),
         }
     }
-
-    pub fn random() -> Self {
-        let shape = Shape::clone(SHAPES.choose(&mut rand::thread_rng()).unwrap());
-
-        Shape::new(shape)
-    }
 }
 
 impl Transform for Shape {
@@ -155,7 +194,12 @@ impl Transform for Shape {
         self.y += dy;
     }
 
-    fn rotate(&mut self, v_dir: VDir, h_dir: HDir) {
+    fn rotate(&mut self, turn: Turn) {
+        let (rotation, v_dir, h_dir) = match turn {
+            Turn::Cw => (self.rotation.cw(), VDir::Bottom, HDir::Left),
+            Turn::Ccw => (self.rotation.ccw(), VDir::Top, HDir::Right),
+        };
+        self.rotation = rotation;
         self.matrix = self.matrix.rotate(v_dir, h_dir)
     }
 }
@@ -174,16 +218,15 @@ mod tests {
     // [0, 1, 1],
     // [0, 1, 0],
     #[test]
-    #[ignore]
     fn rotation_bottom_left_works() {
-        let mut sh = Shape::new(Shape::clone(Shape::get_named("shape0")));
-        sh.rotate(VDir::Bottom, HDir::Left);
+        let mut sh = Shape::new(ShapeKind::T);
+        sh.rotate_cw();
         assert_eq!(
             *sh.matrix.body(),
             vec![
-                vec![BoardCell::Empty, BoardCell::Filled, BoardCell::Empty],
-                vec![BoardCell::Empty, BoardCell::Filled, BoardCell::Filled],
-                vec![BoardCell::Empty, BoardCell::Filled, BoardCell::Empty],
+                vec![BoardCell::Empty, BoardCell::T, BoardCell::Empty],
+                vec![BoardCell::Empty, BoardCell::T, BoardCell::T],
+                vec![BoardCell::Empty, BoardCell::T, BoardCell::Empty],
             ]
         );
     }
@@ -198,27 +241,26 @@ mod tests {
     // [1, 1, 1],
     // [0, 0, 0],
     #[test]
-    #[ignore]
     fn rotation_top_right_works() {
-        let mut sh = Shape::new(Shape::clone(Shape::get_named("shape0")));
+        let mut sh = Shape::new(ShapeKind::T);
         let body = sh.matrix.body().clone();
-        sh.rotate(VDir::Bottom, HDir::Left);
-        sh.rotate(VDir::Top, HDir::Right);
+        sh.rotate_cw();
+        sh.rotate_ccw();
         assert_eq!(*sh.matrix.body(), body);
     }
 
     #[test]
     fn slice_works() {
-        let mut sh = Shape::new(Shape::clone(Shape::get_named("shape0")));
+        let mut sh = Shape::new(ShapeKind::T);
         let (height, width) = sh.matrix.size();
 
         let slice = sh.matrix.slice((0, 0), (1, width));
         assert_eq!(
             slice,
-            vec![[BoardCell::Empty, BoardCell::Filled, BoardCell::Empty]]
+            vec![[BoardCell::Empty, BoardCell::T, BoardCell::Empty]]
         );
 
-        sh.rotate(VDir::Bottom, HDir::Left);
+        sh.rotate_cw();
         let slice = sh.matrix.slice((0, 0), (height, 1));
 
         assert_eq!(
@@ -235,13 +277,13 @@ mod tests {
             slice,
             vec![
                 vec![BoardCell::Empty],
-                vec![BoardCell::Filled],
+                vec![BoardCell::T],
                 vec![BoardCell::Empty]
             ]
         );
 
-        sh.rotate(VDir::Top, HDir::Right);
-        sh.rotate(VDir::Top, HDir::Right);
+        sh.rotate_ccw();
+        sh.rotate_ccw();
         let slice = sh.matrix.slice((0, width - 1), (height, width));
         assert_eq!(
             slice,
@@ -252,4 +294,49 @@ mod tests {
             ]
         );
     }
+
+    // Before:
+    // [0, 1, 0],
+    // [1, 1, 1],
+    // [0, 0, 0],
+
+    // After:
+    // [0, 0, 0],
+    // [1, 1, 1],
+    // [0, 1, 0],
+    #[test]
+    fn rotation_half_turn_works() {
+        let mut sh = Shape::new(ShapeKind::T);
+        sh.rotate_180();
+        assert_eq!(sh.rotation, Rotation::Reverse);
+        assert_eq!(
+            *sh.matrix.body(),
+            vec![
+                vec![BoardCell::Empty, BoardCell::Empty, BoardCell::Empty],
+                vec![BoardCell::T, BoardCell::T, BoardCell::T],
+                vec![BoardCell::Empty, BoardCell::T, BoardCell::Empty],
+            ]
+        );
+    }
+
+    #[test]
+    fn matrix_mirrors_along_diagonals() {
+        let matrix = Matrix::new(ShapeKind::J.body());
+        assert_eq!(
+            *matrix.rotate(VDir::Top, HDir::Left).body(),
+            vec![
+                vec![BoardCell::J, BoardCell::J, BoardCell::Empty],
+                vec![BoardCell::Empty, BoardCell::J, BoardCell::Empty],
+                vec![BoardCell::Empty, BoardCell::J, BoardCell::Empty],
+            ]
+        );
+        assert_eq!(
+            *matrix.rotate(VDir::Bottom, HDir::Right).body(),
+            vec![
+                vec![BoardCell::Empty, BoardCell::J, BoardCell::Empty],
+                vec![BoardCell::Empty, BoardCell::J, BoardCell::Empty],
+                vec![BoardCell::Empty, BoardCell::J, BoardCell::J],
+            ]
+        );
+    }
 }
@@ -0,0 +1,34 @@
+use super::game::Game;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+// Bumped whenever a saved game would no longer load the same.
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Snapshot<G> {
+    version: u32,
+    game: G,
+}
+
+// JSON text rather than a JS object, the RNG state does not fit JS numbers.
+pub fn save(game: &Game) -> String {
+    let snapshot = Snapshot {
+        version: SNAPSHOT_VERSION,
+        game,
+    };
+    serde_json::to_string(&snapshot).unwrap()
+}
+
+// The version is checked first, older saves may not parse as a game at all.
+// Saves that parse but would panic later are turned down as well.
+pub fn load(json: &str) -> Result<Game, String> {
+    let header: Snapshot<IgnoredAny> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if header.version != SNAPSHOT_VERSION {
+        return Err(format!("unsupported snapshot version {}", header.version));
+    }
+
+    let snapshot: Snapshot<Game> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    snapshot.game.check()?;
+    Ok(snapshot.game)
+}
@@ -0,0 +1,95 @@
+use tetris_wasm::models::action::Action;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+    Enter,
+    Backspace,
+    Interrupt,
+    Char(char),
+}
+
+// Raw mode hands over bytes as typed, arrows arrive as escape sequences and
+// several keys may come in a single read.
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = vec![];
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let (key, len) = match rest {
+            // Parameters come first, the final byte names the key.
+            [0x1b, b'[', tail @ ..] | [0x1b, b'O', tail @ ..] => {
+                match tail.iter().position(|b| (0x40..=0x7e).contains(b)) {
+                    Some(end) => (arrow(tail[end]), end + 3),
+                    None => (None, rest.len()),
+                }
+            }
+            [b' ', ..] => (Some(Key::Space), 1),
+            [b'\r', ..] | [b'\n', ..] => (Some(Key::Enter), 1),
+            [0x7f, ..] | [0x08, ..] => (Some(Key::Backspace), 1),
+            [0x03, ..] => (Some(Key::Interrupt), 1),
+            [c, ..] if c.is_ascii_graphic() => (Some(Key::Char(c.to_ascii_lowercase() as char)), 1),
+            _ => (None, 1),
+        };
+        keys.extend(key);
+        rest = &rest[len..];
+    }
+    keys
+}
+
+fn arrow(code: u8) -> Option<Key> {
+    match code {
+        b'A' => Some(Key::Up),
+        b'B' => Some(Key::Down),
+        b'C' => Some(Key::Right),
+        b'D' => Some(Key::Left),
+        _ => None,
+    }
+}
+
+// Keys that play the game, the rest is up to the main loop.
+pub fn action(key: Key) -> Option<Action> {
+    match key {
+        Key::Left => Some(Action::MoveLeft),
+        Key::Right => Some(Action::MoveRight),
+        Key::Down => Some(Action::MoveDown),
+        Key::Up | Key::Char('x') => Some(Action::Rotate),
+        Key::Char('z') => Some(Action::RotateCcw),
+        Key::Char('a') => Some(Action::Rotate180),
+        Key::Space => Some(Action::HardDrop),
+        Key::Char('c') => Some(Action::Hold),
+        Key::Backspace => Some(Action::Undo),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_parse_keys() {
+        let keys = parse_keys(b"\x1b[D\x1b[Ax \x1b[5~Q\x7f");
+        assert_eq!(
+            keys,
+            vec![
+                Key::Left,
+                Key::Up,
+                Key::Char('x'),
+                Key::Space,
+                Key::Char('q'),
+                Key::Backspace
+            ]
+        );
+    }
+
+    #[test]
+    fn check_keys_map_to_actions() {
+        assert_eq!(action(Key::Up), Some(Action::Rotate));
+        assert_eq!(action(Key::Char('z')), Some(Action::RotateCcw));
+        assert_eq!(action(Key::Enter), None);
+    }
+}
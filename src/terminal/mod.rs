@@ -0,0 +1,150 @@
+pub mod input;
+pub mod render;
+
+use input::{action, parse_keys, Key};
+use std::io::{self, Write};
+use std::panic;
+use std::time::{Duration, Instant};
+use tetris_wasm::models::action::Action;
+use tetris_wasm::models::game::Game;
+use tetris_wasm::models::game_status::GameStatus;
+use tetris_wasm::models::settings::Settings;
+
+// Alternate screen with a hidden cursor, so the shell comes back untouched.
+const ENTER_SCREEN: &str = "\x1b[?1049h\x1b[?25l\x1b[2J";
+const LEAVE_SCREEN: &str = "\x1b[?25h\x1b[?1049l";
+
+// Keys are read unbuffered and without echo on the alternate screen for as
+// long as this lives.
+struct RawMode {
+    original: libc::termios,
+}
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        unsafe {
+            let mut termios = std::mem::zeroed::<libc::termios>();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let original = termios;
+            libc::cfmakeraw(&mut termios);
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut out = io::stdout();
+            write!(out, "{}", ENTER_SCREEN)?;
+            out.flush()?;
+            Ok(RawMode { original })
+        }
+    }
+}
+
+fn restore(original: &libc::termios) {
+    let mut out = io::stdout();
+    let _ = write!(out, "{}", LEAVE_SCREEN);
+    let _ = out.flush();
+    unsafe {
+        libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original);
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        restore(&self.original);
+    }
+}
+
+// Waits up to `timeout` for input and returns whatever keys came in.
+fn read_keys(timeout: Duration) -> io::Result<Vec<Key>> {
+    let mut fd = libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let ready = unsafe { libc::poll(&mut fd, 1, timeout.as_millis() as libc::c_int) };
+    if ready < 0 {
+        let error = io::Error::last_os_error();
+        return match error.kind() {
+            io::ErrorKind::Interrupted => Ok(vec![]),
+            _ => Err(error),
+        };
+    }
+    if ready == 0 {
+        return Ok(vec![]);
+    }
+
+    let mut buffer = [0u8; 64];
+    let read = unsafe {
+        libc::read(
+            libc::STDIN_FILENO,
+            buffer.as_mut_ptr() as *mut libc::c_void,
+            buffer.len(),
+        )
+    };
+    if read < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(parse_keys(&buffer[..read as usize]))
+}
+
+// A panic message would get lost on the alternate screen, so the terminal is
+// given back before it is printed.
+pub fn run() -> io::Result<()> {
+    let raw = RawMode::enable()?;
+    let original = raw.original;
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore(&original);
+        default_hook(info);
+    }));
+    play(&mut io::stdout())
+}
+
+// The engine says how long to wait for the next tick, keys are handled as
+// they come in meanwhile.
+fn play(out: &mut impl Write) -> io::Result<()> {
+    let mut game = Game::with_settings(Settings::new());
+    let mut last_tick = Instant::now();
+
+    loop {
+        let state = game.get_state();
+        write!(out, "{}", render::frame(&state))?;
+        out.flush()?;
+
+        let deadline = last_tick + Duration::from_millis(game.interval() as u64);
+        let keys = read_keys(deadline.saturating_duration_since(Instant::now()))?;
+        for key in keys {
+            match (key, game.get_status()) {
+                (Key::Char('q'), _) | (Key::Interrupt, _) => return Ok(()),
+                (Key::Enter, GameStatus::Pending) => {
+                    game.apply(Action::Start);
+                }
+                (Key::Enter, GameStatus::Over) => {
+                    game = Game::with_settings(Settings::new());
+                    game.apply(Action::Start);
+                }
+                (Key::Char('p'), GameStatus::InProgress) => {
+                    game.apply(Action::Pause);
+                }
+                (Key::Char('p'), GameStatus::Paused) => {
+                    game.apply(Action::Resume);
+                }
+                _ => {
+                    if let Some(action) = action(key) {
+                        game.apply(action);
+                    }
+                }
+            }
+        }
+        game.drain_events();
+
+        // Only a running game ticks, the clock starts over once it does.
+        if game.get_status() != GameStatus::InProgress {
+            last_tick = Instant::now();
+        } else if Instant::now() >= deadline {
+            game.apply(Action::Tick);
+            last_tick = Instant::now();
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use tetris_wasm::models::board::BoardCell;
+use tetris_wasm::models::game::GameState;
+use tetris_wasm::models::game_status::{GameOver, GameStatus};
+use tetris_wasm::models::matrix::MatrixBody;
+
+const RESET: &str = "\x1b[0m";
+const PANEL_GAP: &str = "   ";
+
+// Two columns per cell keep the board roughly square in most fonts.
+fn cell(cell: BoardCell) -> String {
+    let background = match cell {
+        BoardCell::Empty => return " .".to_string(),
+        BoardCell::Ghost => return format!("\x1b[2m[]{}", RESET),
+        BoardCell::T => "45",
+        BoardCell::I => "46",
+        BoardCell::O => "43",
+        BoardCell::Z => "41",
+        BoardCell::S => "42",
+        BoardCell::J => "44",
+        BoardCell::L => "48;5;208",
+        BoardCell::Garbage => "100",
+    };
+    format!("\x1b[{}m  {}", background, RESET)
+}
+
+// The spawn rows sit above the walls, the way the guideline draws them.
+fn board(state: &GameState) -> Vec<String> {
+    let mut lines = vec![];
+    for (r, (blocks, ghost)) in state.blocks.iter().zip(state.ghost.iter()).enumerate() {
+        let cells = blocks.iter().zip(ghost.iter()).map(|(&b, &g)| {
+            if b.is_filled() {
+                cell(b)
+            } else if r < state.spawn_rows {
+                "  ".to_string()
+            } else {
+                cell(g)
+            }
+        });
+        let cells = cells.collect::<String>();
+        if r < state.spawn_rows {
+            lines.push(format!("  {}  ", cells));
+        } else {
+            lines.push(format!("<!{}!>", cells));
+        }
+    }
+    lines.push(format!("<!{}!>", "==".repeat(state.width)));
+    lines.push(format!("  {}  ", "\\/".repeat(state.width)));
+    lines
+}
+
+// Previews drop the empty rows of the spawn matrix.
+fn piece(body: &MatrixBody) -> Vec<String> {
+    body.iter()
+        .filter(|row| row.iter().any(|c| c.is_filled()))
+        .map(|row| {
+            row.iter()
+                .map(|&c| {
+                    if c.is_filled() {
+                        cell(c)
+                    } else {
+                        "  ".to_string()
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn status(state: &GameState) -> String {
+    match (state.status, state.over) {
+        (GameStatus::Pending, _) => "Press Enter to start".to_string(),
+        (GameStatus::Paused, _) => "Paused, P to resume".to_string(),
+        (GameStatus::Over, Some(reason)) => format!("Game over: {}", over_reason(reason)),
+        (GameStatus::Over, None) => "Game over".to_string(),
+        (GameStatus::InProgress, _) => String::new(),
+    }
+}
+
+fn over_reason(reason: GameOver) -> &'static str {
+    match reason {
+        GameOver::BlockOut => "block out",
+        GameOver::LockOut => "lock out",
+        GameOver::TopOut => "top out",
+    }
+}
+
+fn panel(state: &GameState) -> Vec<String> {
+    let score = &state.score;
+    let mut lines = vec!["HOLD".to_string()];
+    lines.extend(state.hold.as_ref().map_or(vec![String::new()], piece));
+    lines.push(String::new());
+
+    lines.push("NEXT".to_string());
+    for body in state.next.iter() {
+        lines.extend(piece(body));
+        lines.push(String::new());
+    }
+
+    lines.push(format!("Score  {}", score.points));
+    lines.push(format!("Lines  {}", score.lines));
+    lines.push(format!("Level  {}", score.level));
+    if score.combo > 1 {
+        lines.push(format!("Combo  {}", score.combo - 1));
+    }
+    if score.back_to_back {
+        lines.push("Back-to-back".to_string());
+    }
+    lines.push(String::new());
+    lines.push(status(state));
+    lines.push(String::new());
+    lines.push("Arrows move, Up/X/Z/A rotate".to_string());
+    lines.push("Space drop, C hold, Backspace undo".to_string());
+    lines.push("P pause, Q quit".to_string());
+    lines
+}
+
+// A whole frame, drawn over the previous one from the top left corner.
+pub fn frame(state: &GameState) -> String {
+    let board = board(state);
+    let panel = panel(state);
+    let blank = " ".repeat(state.width * 2 + 4);
+
+    let mut out = String::from("\x1b[H");
+    for i in 0..board.len().max(panel.len()) {
+        out += board.get(i).unwrap_or(&blank);
+        out += PANEL_GAP;
+        out += panel.get(i).map_or("", String::as_str);
+        out += "\x1b[K\r\n";
+    }
+    out += "\x1b[J";
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tetris_wasm::models::game::Game;
+
+    #[test]
+    fn check_frame_draws_every_row() {
+        let state = Game::with_seed(0).get_state();
+        let frame = frame(&state);
+        let walls = frame.matches("<!").count();
+        assert_eq!(walls, state.height + 1);
+        assert!(frame.contains("Press Enter to start"));
+    }
+}
@@ -1,13 +1,11 @@
 import { assertHtmlElement } from './model/errors';
 import { GamePresenter as GamePresenter } from './model/game';
-import { CanvasOverlayRenderer, CanvasRenderer, ControlsRenderer, GameView } from "./model/views";
+import { CanvasOverlayRenderer, CanvasRenderer, ControlsRenderer, GameView, PreviewRenderer, ScoreRenderer } from "./model/views";
 import { ContcreteTetris } from "./model/tetris";
 import { ConcrecetGameStateExtended } from './model/state';
 
-export const BOARD_SIZE = Object.freeze({ x: 10, y: 18 })
 const usize = 24
-const width = usize * BOARD_SIZE.x
-const height = usize * BOARD_SIZE.y
+const PREVIEW_SLOTS = 5
 
 async function main() {
   try {
@@ -16,15 +14,22 @@ async function main() {
     const $controls = $app?.querySelector<HTMLDivElement>('#controls')
     const $canvasOverlay = $app?.querySelector<HTMLDivElement>('#canvas-overlay')
     const $canvas = $app?.querySelector<HTMLCanvasElement>('#canvas')
+    const $score = $app?.querySelector<HTMLDivElement>('#score')
+    const $next = $app?.querySelector<HTMLCanvasElement>('#next')
+    const $hold = $app?.querySelector<HTMLCanvasElement>('#hold')
   
     assertHtmlElement($container, 'container')
     assertHtmlElement($controls, 'controls')
     assertHtmlElement($canvasOverlay, 'canvas-overlay')
     assertHtmlElement($canvas, 'canvas')
+    assertHtmlElement($score, 'score')
+    assertHtmlElement($next, 'next')
+    assertHtmlElement($hold, 'hold')
   
-    $canvas.width = width * 2
-    $canvas.height = height * 2
-    $canvas.style.setProperty('--ratio', (BOARD_SIZE.x / BOARD_SIZE.y).toString())
+    $next.width = usize * 4
+    $next.height = usize * 4 * PREVIEW_SLOTS
+    $hold.width = usize * 4
+    $hold.height = usize * 4
     
     const tetris = new ContcreteTetris()
     
@@ -32,14 +37,18 @@ async function main() {
 
     const view = new GameView(document);
     
-    view.addComponent(new CanvasRenderer(view, $canvas))
-    view.addComponent(new CanvasOverlayRenderer(view, $canvasOverlay))
+    view.addComponent(new CanvasRenderer(view, $canvas, usize * 2))
+    view.addComponent(new CanvasOverlayRenderer(view, $canvasOverlay, state))
     view.addComponent(new ControlsRenderer(view, $controls))
+    view.addComponent(new ScoreRenderer(view, $score))
+    view.addComponent(new PreviewRenderer(view, $next, (state) => state.next, PREVIEW_SLOTS))
+    view.addComponent(new PreviewRenderer(view, $hold, (state) => state.hold ? [state.hold] : [], 1))
     
-    const presenter = new GamePresenter(state, view, tetris, 1000)
+    const presenter = new GamePresenter(state, view, tetris)
     
     await presenter.load()
-    presenter.create()
+    if (!presenter.restore()) presenter.create()
+    window.addEventListener('pagehide', presenter.save)
   } catch (e){
     console.error(e)
   }
@@ -1,10 +1,41 @@
 export enum GameStatus {
   Pending = 0,
   InProgress = 1,
-  Over = 2
+  Over = 2,
+  Paused = 3
+}
+
+export enum ShapeKind {
+  T = 0,
+  I = 1,
+  O = 2,
+  Z = 3,
+  S = 4,
+  J = 5,
+  L = 6
+}
+
+export enum Spin {
+  None = 0,
+  Mini = 1,
+  Full = 2
+}
+
+export enum GameOver {
+  BlockOut = 0,
+  LockOut = 1,
+  TopOut = 2
 }
 
 export enum BoardCell {
   Empty = 0,
-  Filled = 1
+  T = 1,
+  I = 2,
+  O = 3,
+  Z = 4,
+  S = 5,
+  J = 6,
+  L = 7,
+  Garbage = 8,
+  Ghost = 9
 }
\ No newline at end of file
@@ -1,14 +1,16 @@
+import { GameStatus } from "./enums"
 import { Tetris } from "./tetris"
-import { GameStateExtended, Presenter, View } from "./types"
+import { GameState, GameStateExtended, Presenter, View } from "./types"
+
+const SAVE_KEY = 'tetris-save'
 
 export class GamePresenter implements Presenter {
-  private interval: number | null = null
+  private timeout: number | null = null
   
   constructor(    
     private state: GameStateExtended,
     private view: View,
-    private game: Tetris,
-    private step: number
+    private game: Tetris
   ) {
     this.state.on('next', this.render)
     this.state.on('paused', this.handlePaused)
@@ -16,9 +18,14 @@ export class GamePresenter implements Presenter {
     this.state.on('over', this.stop)
     
     this.view.on('rotate', this.rotate)
+    this.view.on('rotateCcw', this.rotateCcw)
+    this.view.on('rotate180', this.rotate180)
     this.view.on('moveLeft', this.moveLeft)
     this.view.on('moveRight', this.moveRight)
     this.view.on('moveDown', this.moveDown)
+    this.view.on('hardDrop', this.hardDrop)
+    this.view.on('hold', this.hold)
+    this.view.on('undo', this.undo)
     this.view.on('start', this.start)
     this.view.on('pause', this.pause)
     this.view.on('resume', this.resume)
@@ -30,37 +37,91 @@ export class GamePresenter implements Presenter {
   }
 
   rotate = () => {
-    this.state.updateGameState(this.game.rotate())
+    this.update(this.game.rotate())
+  }
+
+  rotateCcw = () => {
+    this.update(this.game.rotateCcw())
+  }
+
+  rotate180 = () => {
+    this.update(this.game.rotate180())
   }
 
   moveLeft = () => {
-    this.state.updateGameState(this.game.moveLeft())
+    this.update(this.game.moveLeft())
   }
 
   moveRight = () => {
-    this.state.updateGameState(this.game.moveRight())
+    this.update(this.game.moveRight())
   }
 
   moveDown = () => {
-    this.state.updateGameState(this.game.moveDown())
+    this.update(this.game.moveDown())
+  }
+
+  hardDrop = () => {
+    this.update(this.game.hardDrop())
+  }
+
+  hold = () => {
+    this.update(this.game.hold())
+  }
+
+  // Taking back the piece that ended the game starts the clock again.
+  undo = () => {
+    this.update(this.game.undo())
+    this.schedule()
   }
 
   create = () => {
-    this.state.updateGameState(this.game.create())
+    this.state.updateGameState(this.game.create(), this.game.events())
+  }
+
+  // Keeps an unfinished game around for the next page load.
+  save = () => {
+    if (this.state.status !== GameStatus.InProgress && this.state.status !== GameStatus.Paused) {
+      localStorage.removeItem(SAVE_KEY)
+      return
+    }
+
+    try {
+      localStorage.setItem(SAVE_KEY, this.game.save())
+    } catch (e) {
+      console.warn('could not save the game', e)
+    }
+  }
+
+  // A restored game comes back paused, so the pieces don't start falling
+  // before the player is ready.
+  restore = (): boolean => {
+    const saved = localStorage.getItem(SAVE_KEY)
+    localStorage.removeItem(SAVE_KEY)
+    if (saved === null) return false
+
+    try {
+      this.state.updateGameState(this.game.restore(saved), this.game.events())
+    } catch (e) {
+      console.warn('could not restore the saved game', e)
+      return false
+    }
+    this.view.addListeners()
+    if (this.state.status === GameStatus.InProgress) this.pause()
+    return true
   }
 
   pause = () => {
-    this.state.updatePaused(true)
+    this.state.updateGameState(this.game.pause(), this.game.events())
   }
 
   resume = () => {
-    this.state.updatePaused(false)
+    this.state.updateGameState(this.game.resume(), this.game.events())
   }
 
   start = () =>  {
     this.view.addListeners()
-    this.state.updateGameState(this.game.start())
-    this.setInterval()
+    this.state.updateGameState(this.game.start(), this.game.events())
+    this.schedule()
   }
 
   restart = () => {
@@ -68,19 +129,19 @@ export class GamePresenter implements Presenter {
     this.start()
   } 
 
+  // Keys stay on after a game over, so the last piece can be taken back.
   stop = (state: GameStateExtended) => {
-    this.clearInterval()
-    this.view.removeListeners()
+    this.cancel()
     this.render(state)
   }
 
   handlePaused = (state: GameStateExtended): void => {
-    this.clearInterval()
+    this.cancel()
     this.render(state)
   }
 
   handleResumed = (state: GameStateExtended): void => {
-    this.setInterval()
+    this.schedule()
     this.render(state)
   }
 
@@ -88,16 +149,30 @@ export class GamePresenter implements Presenter {
     this.view.render(state)
   }
 
+  // The engine may want ticks sooner or later after an action,
+  // e.g. every frame once the piece has landed and the lock timer runs.
+  update(state: GameState): void {
+    const interval = this.state.interval
+    this.state.updateGameState(state, this.game.events())
+    if (this.timeout !== null && this.state.interval !== interval) {
+      this.cancel()
+      this.schedule()
+    }
+  }
+
   tick = () => {
-    this.state.updateGameState(this.game.tick())
+    this.timeout = null
+    this.state.updateGameState(this.game.tick(), this.game.events())
+    this.schedule()
   }
 
-  setInterval(): void {
-    this.interval = setInterval(this.tick, this.step)
+  schedule(): void {
+    if (this.timeout !== null || this.state.status !== GameStatus.InProgress) return
+    this.timeout = setTimeout(this.tick, this.state.interval)
   }
 
-  clearInterval(): void {
-    this.interval && clearInterval(this.interval)
-    this.interval = null
+  cancel(): void {
+    this.timeout && clearTimeout(this.timeout)
+    this.timeout = null
   }
 }
\ No newline at end of file
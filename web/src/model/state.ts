@@ -1,30 +1,57 @@
 import EventEmitter from 'eventemitter3';
-import { BoardCell, GameStatus } from './enums';
-import { GameEventTypes, GameState, GameStateExtended } from './types';
+import { BoardCell, GameOver, GameStatus } from './enums';
+import { GameEvent, GameEventTypes, GameState, GameStateExtended, Score } from './types';
 
 export class ConcrecetGameStateExtended implements GameStateExtended {
-  paused = false
+  width = 0
+  height = 0
+  spawn_rows = 0
   blocks: BoardCell[][] = []
+  ghost: BoardCell[][] = []
   status: GameStatus = GameStatus.Pending
+  over: GameOver | null = null
+  rejected = false
+  score: Score = {
+    points: 0,
+    lines: 0,
+    level: 1,
+    singles: 0,
+    doubles: 0,
+    triples: 0,
+    tetrises: 0,
+    tspins: 0,
+    combo: 0,
+    back_to_back: false,
+    perfect_clears: 0
+  }
+  interval = 1000
+  next: BoardCell[][][] = []
+  hold: BoardCell[][] | null = null
   emitter = new EventEmitter<GameEventTypes>()
 
   on = this.emitter.on.bind(this.emitter)
 
   off = this.emitter.off.bind(this.emitter)
 
-  updateGameState(state: GameState) {
-    const isOver = this.status !== GameStatus.Over && state.status === GameStatus.Over
+  get paused() {
+    return this.status === GameStatus.Paused
+  }
+
+  updateGameState(state: GameState, events: GameEvent[] = []) {
+    const previous = this.status
     Object.assign(this, state)
-    if(isOver){
+    events.forEach((event) => this.emitter.emit('event', event, this))
+    if (previous === this.status) {
+      this.emitter.emit('next', this)
+    } else if (this.status === GameStatus.Over) {
       this.emitter.emit('over', this)
-      return
+    } else if (this.status === GameStatus.Paused) {
+      this.emitter.emit('paused', this)
+    } else if (previous === GameStatus.Paused) {
+      this.emitter.emit('resumed', this)
+    } else {
+      this.emitter.emit('next', this)
     }
-    this.emitter.emit('next', this)
-  }
-  
-  updatePaused(paused: boolean) {
-    this.paused = paused
-    this.emitter.emit(paused ? 'paused' : 'resumed', this)
   }
 
   subscribe(type: GameEventTypes, fn: (...args: any)=>void){
@@ -1,18 +1,32 @@
-import init, {Game} from "tetris-wasm"
-import { GameState } from "./types"
+import init, {Game, Replay} from "tetris-wasm"
+import { GameEvent, GameState, Recording } from "./types"
 
 export interface Tetris {
   load(): void
 
   create(): GameState
+  // Saved games are JSON text, opaque to the frontend.
+  save(): string
+  restore(snapshot: string): GameState
 
   start(): GameState
+  pause(): GameState
+  resume(): GameState
   tick(): GameState
 
   rotate(): GameState
+  rotateCcw(): GameState
+  rotate180(): GameState
   moveLeft(): GameState
   moveRight(): GameState
   moveDown(): GameState
+  hold(): GameState
+  hardDrop(): GameState
+  addGarbage(rows: number): GameState
+  undo(): GameState
+  events(): GameEvent[]
+  recording(): Recording
+  replay(recording: Recording): Replay
 }
 
 
@@ -22,7 +36,17 @@ export class ContcreteTetris implements Tetris {
   load = init
 
   create(){
-    this.game = Game.new()
+    this.game = Game.standard()
+    return this.game.to_js()
+  }
+
+  save = (): string => {
+    return this.game.save()
+  }
+
+  // Throws for corrupt or outdated saves.
+  restore = (snapshot: string): GameState => {
+    this.game = Game.load(snapshot)
     return this.game.to_js()
   }
 
@@ -30,6 +54,14 @@ export class ContcreteTetris implements Tetris {
     return this.game.start()
   }
 
+  pause = (): GameState => {
+    return this.game.pause()
+  }
+
+  resume = (): GameState => {
+    return this.game.resume()
+  }
+
   tick = (): GameState => {
     return this.game.tick()
   }
@@ -37,6 +69,14 @@ export class ContcreteTetris implements Tetris {
   rotate = (): GameState => {
     return this.game.rotate()
   }
+
+  rotateCcw = (): GameState => {
+    return this.game.rotate_ccw()
+  }
+
+  rotate180 = (): GameState => {
+    return this.game.rotate_180()
+  }
   
   moveLeft = (): GameState => {
     return this.game.move_left()
@@ -49,4 +89,32 @@ export class ContcreteTetris implements Tetris {
   moveDown = (): GameState => {
     return this.game.move_down()
   }
+
+  hold = (): GameState => {
+    return this.game.hold()
+  }
+
+  hardDrop = (): GameState => {
+    return this.game.hard_drop()
+  }
+
+  addGarbage = (rows: number): GameState => {
+    return this.game.add_garbage(rows)
+  }
+
+  undo = (): GameState => {
+    return this.game.undo()
+  }
+
+  events = (): GameEvent[] => {
+    return this.game.events()
+  }
+
+  recording = (): Recording => {
+    return this.game.recording()
+  }
+
+  replay = (recording: Recording): Replay => {
+    return Replay.from_js(recording)
+  }
 }
@@ -1,19 +1,79 @@
 import type EventEmitter from "eventemitter3";
-import { BoardCell, GameStatus } from "./enums";
+import { BoardCell, GameOver, GameStatus, ShapeKind, Spin } from "./enums";
+
+export interface Score {
+  points: number,
+  lines: number,
+  level: number,
+  singles: number,
+  doubles: number,
+  triples: number,
+  tetrises: number,
+  tspins: number,
+  combo: number,
+  back_to_back: boolean,
+  perfect_clears: number
+}
 
 export interface GameState {
+  width: number,
+  height: number,
+  spawn_rows: number,
   blocks: BoardCell[][],
-  status: GameStatus
+  ghost: BoardCell[][],
+  status: GameStatus,
+  over: GameOver | null,
+  rejected: boolean,
+  score: Score,
+  interval: number,
+  next: BoardCell[][][],
+  hold: BoardCell[][] | null
+}
+
+export type GameEvent =
+  | { type: 'PieceSpawned', kind: ShapeKind }
+  | { type: 'PieceLocked', kind: ShapeKind, spin: Spin }
+  | { type: 'PieceHeld', kind: ShapeKind }
+  | { type: 'Undone', kind: ShapeKind }
+  | { type: 'LinesCleared', rows: number[] }
+  | { type: 'PerfectClear' }
+  | { type: 'RotationFailed' }
+  | { type: 'LevelUp', level: number }
+  | { type: 'GameOver', reason: GameOver }
+  | { type: 'ActionRejected' }
+
+export type Action =
+  | 'Start' | 'Pause' | 'Resume' | 'Tick'
+  | 'MoveLeft' | 'MoveRight' | 'MoveDown' | 'HardDrop'
+  | 'Rotate' | 'RotateCcw' | 'Rotate180' | 'Hold' | 'Undo'
+  | { Garbage: { rows: number } }
+
+export interface Recording {
+  settings: {
+    preview: number,
+    randomizer: number,
+    // Kept as a string, most seeds are past Number.MAX_SAFE_INTEGER.
+    seed: string,
+    lock_delay: number,
+    lock_resets: number,
+    width: number,
+    height: number,
+    buffer: number,
+    undo: number,
+    ranked: boolean
+  },
+  sequence: number[] | null,
+  steps: { frame: number, action: Action }[],
+  frames: number
 }
 
-export type GameEventTypes = 'next' | 'paused' | 'resumed' | 'over'
+export type GameEventTypes = 'next' | 'paused' | 'resumed' | 'over' | 'event'
 
 type GameEmitter = Pick<EventEmitter<GameEventTypes>, 'on' | 'off'>
 
 export interface GameStateExtended extends GameState, GameEmitter {
-  paused: boolean
-  updateGameState(state: GameState): void
-  updatePaused(paused: boolean): void
+  readonly paused: boolean
+  updateGameState(state: GameState, events?: GameEvent[]): void
   blocksIndexes(): Generator<[number, number]>
 }
 
@@ -25,9 +85,14 @@ export interface Presenter {
   pause(): void
 
   rotate(): void
+  rotateCcw(): void
+  rotate180(): void
   moveLeft(): void
   moveRight(): void
   moveDown(): void
+  hold(): void
+  hardDrop(): void
+  undo(): void
 }
 
 export interface Component {
@@ -35,7 +100,7 @@ export interface Component {
   render(state: GameStateExtended): void
 }
 
-export type ViewEventTypes = 'rotate' | 'moveLeft' | 'moveRight' | 'moveDown' | 'start' | 'pause' | 'resume' | 'restart'
+export type ViewEventTypes = 'rotate' | 'rotateCcw' | 'rotate180' | 'moveLeft' | 'moveRight' | 'moveDown' | 'hardDrop' | 'hold' | 'undo' | 'start' | 'pause' | 'resume' | 'restart'
 
 type ViewEmitter = Pick<EventEmitter<ViewEventTypes>, 'on' | 'off'>
 
@@ -3,14 +3,33 @@ import EventEmitter from 'eventemitter3';
 
 import { Cell } from "./drawable";
 import { assertHtmlElement } from './errors';
-import type { Component, GameStateExtended, View, ViewEventTypes } from './types';
-import { BoardCell, GameStatus } from './enums';
+import type { Component, GameEvent, GameStateExtended, View, ViewEventTypes } from './types';
+import { BoardCell, GameOver, GameStatus } from './enums';
 
 const KEY_2_EVENT: Record<string, ViewEventTypes> = Object.freeze({
   ArrowUp: 'rotate',
+  KeyX: 'rotate',
+  KeyZ: 'rotateCcw',
+  KeyA: 'rotate180',
   ArrowLeft: 'moveLeft',
   ArrowRight: 'moveRight',
   ArrowDown: 'moveDown',
+  Space: 'hardDrop',
+  KeyC: 'hold',
+  Backspace: 'undo',
+})
+
+const CELL_COLORS: Record<BoardCell, string> = Object.freeze({
+  [BoardCell.Empty]: '#FFFFFF',
+  [BoardCell.T]: '#8e3bb8',
+  [BoardCell.I]: '#1fa3c7',
+  [BoardCell.O]: '#e0b42a',
+  [BoardCell.Z]: '#cc3b3b',
+  [BoardCell.S]: '#4fa83d',
+  [BoardCell.J]: '#2f55b5',
+  [BoardCell.L]: '#e07b24',
+  [BoardCell.Garbage]: '#7d8796',
+  [BoardCell.Ghost]: '#c5d6ea',
 })
 
 export class GameView implements View {
@@ -56,16 +75,31 @@ export class CanvasRenderer implements Component {
   private ctx: CanvasRenderingContext2D
   constructor(
     public view: View,
-    root: HTMLCanvasElement
+    private root: HTMLCanvasElement,
+    private cellSize: number
   ){
     const context = root.getContext('2d')
     invariant(context, 'Context is null');
     this.ctx = context
   }
 
+  // The board size is only known once the engine reports it.
+  // Spawn rows sit on top of the visible field.
+  resize(state: GameStateExtended): void {
+    const rows = state.height + state.spawn_rows
+    const width = this.cellSize * state.width
+    const height = this.cellSize * rows
+    if (this.root.width === width && this.root.height === height) return
+
+    this.root.width = width
+    this.root.height = height
+    this.root.style.setProperty('--ratio', (state.width / rows).toString())
+  }
+
   render(state: GameStateExtended): void {
     const gap = 1
-    
+
+    this.resize(state)
     const {height, width} = this.ctx.canvas;
 
     const cellHeight = Math.floor(height / state.blocks.length)
@@ -79,12 +113,26 @@ export class CanvasRenderer implements Component {
       w: width,
       h: height,
     })
+    new Cell(this.ctx, '#eef3f9').draw({
+      x: 0,
+      y: 0,
+      w: width,
+      h: state.spawn_rows * cellHeight,
+    })
     for (const [r, c] of state.blocksIndexes()) {
       if(state.blocks[r][c] === BoardCell.Empty){
+        if(state.ghost[r][c] !== BoardCell.Empty){
+          new Cell(this.ctx, CELL_COLORS[BoardCell.Ghost]).draw({
+            x: c * cellWidth + gap,
+            y: r * cellHeight + gap,
+            w: cellWidth - 2 * gap,
+            h: cellHeight - 2 * gap,
+          })
+        }
         continue;
       }
 
-      new Cell(this.ctx, '#003e88').draw({
+      new Cell(this.ctx, CELL_COLORS[state.blocks[r][c]]).draw({
         x: c * cellWidth + gap,
         y: r * cellHeight + gap,
         w: cellWidth - 2 * gap,
@@ -94,11 +142,69 @@ export class CanvasRenderer implements Component {
   }
 }
 
+export class PreviewRenderer implements Component {
+  private ctx: CanvasRenderingContext2D
+  constructor(
+    public view: View,
+    root: HTMLCanvasElement,
+    private select: (state: GameStateExtended) => BoardCell[][][],
+    private slots: number
+  ){
+    const context = root.getContext('2d')
+    invariant(context, 'Context is null');
+    this.ctx = context
+  }
+
+  render(state: GameStateExtended): void {
+    const gap = 1
+
+    const {height, width} = this.ctx.canvas;
+
+    const slotHeight = Math.floor(height / this.slots)
+    const cellSize = Math.floor(Math.min(width, slotHeight) / 4)
+
+    this.ctx.clearRect(0, 0, width, height)
+
+    this.select(state).forEach((shape, i) => {
+      shape.forEach((row, r) => row.forEach((cell, c) => {
+        if (cell === BoardCell.Empty) {
+          return
+        }
+
+        new Cell(this.ctx, CELL_COLORS[cell]).draw({
+          x: c * cellSize + gap,
+          y: i * slotHeight + r * cellSize + gap,
+          w: cellSize - 2 * gap,
+          h: cellSize - 2 * gap,
+        })
+      }))
+    })
+  }
+}
+
+const GAME_OVER_TEXT: Record<GameOver, string> = Object.freeze({
+  [GameOver.BlockOut]: 'Block out',
+  [GameOver.LockOut]: 'Lock out',
+  [GameOver.TopOut]: 'Top out',
+})
+
+const CELEBRATION_MS = 1000
+const PERFECT_CLEAR_TEXT = 'Perfect clear!'
+
 export class CanvasOverlayRenderer implements Component {
+  private celebrating: number | null = null
+
+  // Events come in before the state they led to is rendered.
   constructor(
     public view: View,
-    private root: HTMLDivElement
+    private root: HTMLDivElement,
+    state: GameStateExtended
   ){
+    state.on('event', this.handleEvent)
+  }
+
+  handleEvent = (event: GameEvent) => {
+    if (event.type === 'PerfectClear') this.celebrate()
   }
 
   render(state: GameStateExtended){
@@ -106,15 +212,48 @@ export class CanvasOverlayRenderer implements Component {
       this.root.dataset.text = `
     Press "Start".
   Use arrow keys
-    to move and rotate the figure.`
+    to move and rotate the figure,
+    "Z" and "A" to turn it back or around.
+    Press "Space" to drop it.
+    Press "C" to hold it,
+    "Backspace" to take it back.`
     } else if (state.status === GameStatus.Over) {
-      this.root.dataset.text = 'Game over'
+      this.root.dataset.text = state.over === null ? 'Game over' : `Game over
+  ${GAME_OVER_TEXT[state.over]}`
     } else if (state.paused) {
       this.root.dataset.text = 'Game paused'
+    } else if (this.celebrating !== null) {
+      this.root.dataset.text = PERFECT_CLEAR_TEXT
     } else {
       delete this.root.dataset.text
     }
   }
+
+  celebrate(){
+    this.celebrating && clearTimeout(this.celebrating)
+    this.celebrating = setTimeout(() => {
+      this.celebrating = null
+      if (this.root.dataset.text === PERFECT_CLEAR_TEXT) {
+        delete this.root.dataset.text
+      }
+    }, CELEBRATION_MS)
+  }
+}
+
+export class ScoreRenderer implements Component {
+  constructor(
+    public view: View,
+    private root: HTMLElement
+  ){
+  }
+
+  render({ score }: GameStateExtended){
+    const bonus = [
+      score.combo > 1 ? `Combo: ${score.combo - 1}` : '',
+      score.back_to_back ? 'Back-to-back' : ''
+    ].filter(Boolean).join('  ')
+    this.root.textContent = `Score: ${score.points}  Lines: ${score.lines}  Level: ${score.level}  T-spins: ${score.tspins}  ${bonus}`.trim()
+  }
 }
 
 export class ControlsRenderer implements Component {
@@ -162,8 +301,8 @@ export class ControlsRenderer implements Component {
 
   render(state: GameStateExtended){
     this.$start.hidden = state.status !== GameStatus.Pending
-    this.$pause.hidden = state.status !== GameStatus.InProgress || state.paused
-    this.$resume.hidden = state.status !== GameStatus.InProgress || !state.paused
+    this.$pause.hidden = state.status !== GameStatus.InProgress
+    this.$resume.hidden = state.status !== GameStatus.Paused
     this.$restart.hidden = state.status !== GameStatus.Over
   }
 }
\ No newline at end of file